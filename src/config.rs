@@ -8,6 +8,14 @@ pub struct ExarpConfig {
     pub restic: ResticConfig,
     #[serde(default)]
     pub alerts: AlertConfig,
+    #[serde(default)]
+    pub forget: ForgetConfig,
+    /// Additional repositories to fan out to alongside (or instead of) `[restic]`, e.g. a local
+    /// repo plus an offsite one. See `restic::MultiRunner`.
+    #[serde(default)]
+    pub repositories: Vec<RepoTarget>,
+    #[serde(default)]
+    pub watch: WatchConfig,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -22,6 +30,18 @@ pub struct ResticConfig {
     pub expected_interval_hours: Option<u64>,
 }
 
+/// One entry in `[[repositories]]`: a named restic repository fanned out to by
+/// `restic::MultiRunner`, independent of the single repo configured under `[restic]`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RepoTarget {
+    /// Label used in breakdown tables and JSON output (e.g. "local", "offsite")
+    pub name: String,
+    /// Path to restic binary for this repo (default: falls back to `[restic].binary`, then "restic")
+    pub binary: Option<String>,
+    pub repository: String,
+    pub password_file: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AlertConfig {
     /// % of files changed between snapshots to flag as anomaly (default: 20)
@@ -30,6 +50,35 @@ pub struct AlertConfig {
     pub drift_size_decrease_pct: f64,
     /// Hours without backup before alerting (default: 48)
     pub missed_backup_hours: u64,
+    /// Entropy climb (bits/byte) across a source's tracked history to flag as a spike (default: 1.5)
+    pub entropy_spike_threshold: f64,
+    /// Enable `rules::EntropySpikeRule` (default: true)
+    pub enable_entropy_spike: bool,
+    /// Enable `rules::MassFileChangeRule` (default: true)
+    pub enable_mass_file_change: bool,
+    /// Enable `rules::SizeCollapseRule` (default: true)
+    pub enable_size_collapse: bool,
+    /// Enable `rules::MissedBackupRule` (default: true)
+    pub enable_missed_backup: bool,
+}
+
+/// Default retention policy for `exarp restic forget`, loadable from `[forget]` in config.toml so a
+/// bare `exarp restic forget` (no flags) applies the operator's own standing policy rather than
+/// restic's keep-everything default.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ForgetConfig {
+    pub keep_last: Option<u32>,
+    pub keep_hourly: Option<u32>,
+    pub keep_daily: Option<u32>,
+    pub keep_weekly: Option<u32>,
+    pub keep_monthly: Option<u32>,
+    pub keep_yearly: Option<u32>,
+    /// Evaluate the policy separately per host (default: true, matching restic's own default)
+    pub group_by_host: Option<bool>,
+    /// Evaluate the policy separately per snapshot path set (default: true)
+    pub group_by_paths: Option<bool>,
+    /// Evaluate the policy separately per tag set (default: false)
+    pub group_by_tags: Option<bool>,
 }
 
 impl Default for AlertConfig {
@@ -38,6 +87,38 @@ impl Default for AlertConfig {
             drift_file_change_pct: 20.0,
             drift_size_decrease_pct: 10.0,
             missed_backup_hours: 48,
+            entropy_spike_threshold: 1.5,
+            enable_entropy_spike: true,
+            enable_mass_file_change: true,
+            enable_size_collapse: true,
+            enable_missed_backup: true,
+        }
+    }
+}
+
+/// Task intervals and toggles for `restic::cmd_watch`'s resident monitoring loop.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WatchConfig {
+    /// How often to poll `snapshots`/`stats` for freshness (default: 300s)
+    pub status_interval_secs: u64,
+    /// How often to run `restic check` (default: 86400s — once a day)
+    pub check_interval_secs: u64,
+    /// How often to re-diff the latest snapshot pair for drift (default: 3600s)
+    pub drift_interval_secs: u64,
+    pub enable_status: bool,
+    pub enable_check: bool,
+    pub enable_drift: bool,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            status_interval_secs: 300,
+            check_interval_secs: 86_400,
+            drift_interval_secs: 3_600,
+            enable_status: true,
+            enable_check: true,
+            enable_drift: true,
         }
     }
 }
@@ -1,4 +1,9 @@
+use crate::config::ExarpConfig;
+use crate::preview::{self, FileMeta};
+use crate::restic::{ResticRunner, Snapshot};
+use crate::rules::{self, Diagnostic, RuleContext, Severity};
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use crossterm::{
     event::{self, Event, KeyCode, KeyEventKind},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
@@ -8,8 +13,115 @@ use ratatui::{
     prelude::*,
     widgets::*,
 };
+use futures_util::StreamExt;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
 use std::io::stdout;
+use std::sync::mpsc as std_mpsc;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
+use tokio::sync::mpsc as tokio_mpsc;
+use walkdir::WalkDir;
+
+/// Bytes sampled from the head/tail/middle of a file that's too large to read whole —
+/// mirrors `file_stats`'s sampling budget in main.rs.
+const SAMPLE_SIZE: u64 = 65536;
+
+/// Once fs events stop arriving for this long, a touched batch is considered settled and
+/// gets folded into the source's aggregate on the next tick.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Per-file `(size, entropy)` cache so a single changed file can be folded into a source's
+/// size-weighted mean without re-walking the whole tree.
+type FileCache = HashMap<String, (u64, f64)>;
+
+/// Fold a per-file cache into the size-weighted mean entropy `scan_source` used to compute
+/// from a fresh walk — shared so incremental and full rescans produce identical aggregates.
+fn aggregate_cache(cache: &FileCache) -> f64 {
+    let mut weighted_sum = 0.0_f64;
+    let mut total_bytes = 0u64;
+    for &(size, h) in cache.values() {
+        weighted_sum += h * size as f64;
+        total_bytes += size;
+    }
+    if total_bytes > 0 {
+        weighted_sum / total_bytes as f64
+    } else {
+        0.0
+    }
+}
+
+fn total_bytes(cache: &FileCache) -> u64 {
+    cache.values().map(|&(size, _)| size).sum()
+}
+
+/// Read up to `SAMPLE_SIZE` bytes from a file. Files larger than three sample windows are
+/// sampled from the head, middle, and tail instead of just the head, so entropy spikes
+/// confined to the body or end of a file (partial/tail encryption) still move the average.
+fn sample_file(path: &Path, size: u64) -> Option<Vec<u8>> {
+    let mut file = fs::File::open(path).ok()?;
+
+    if size <= SAMPLE_SIZE * 3 {
+        let mut buffer = vec![0u8; size.min(SAMPLE_SIZE) as usize];
+        let read = file.read(&mut buffer).ok()?;
+        buffer.truncate(read);
+        return Some(buffer);
+    }
+
+    let chunk = (SAMPLE_SIZE / 3).max(1);
+    let mut sample = Vec::with_capacity(chunk as usize * 3);
+
+    let mut head = vec![0u8; chunk as usize];
+    let read = file.read(&mut head).ok()?;
+    head.truncate(read);
+    sample.extend_from_slice(&head);
+
+    file.seek(SeekFrom::Start(size / 2)).ok()?;
+    let mut middle = vec![0u8; chunk as usize];
+    let read = file.read(&mut middle).ok()?;
+    middle.truncate(read);
+    sample.extend_from_slice(&middle);
+
+    file.seek(SeekFrom::Start(size.saturating_sub(chunk))).ok()?;
+    let mut tail = vec![0u8; chunk as usize];
+    let read = file.read(&mut tail).ok()?;
+    tail.truncate(read);
+    sample.extend_from_slice(&tail);
+
+    Some(sample)
+}
+
+/// Compute `(size, entropy)` for a single file, or `None` if it's empty, unreadable, or too
+/// small to sample meaningfully (matching `file_stats`'s 64-byte floor in main.rs).
+fn analyze_source_file(path: &Path) -> Option<(u64, f64)> {
+    let size = fs::metadata(path).ok()?.len();
+    if size == 0 {
+        return None;
+    }
+    let sample = sample_file(path, size)?;
+    if sample.len() < 64 {
+        return None;
+    }
+    Some((size, crate::entropy(&sample)))
+}
+
+/// Walk `path` from scratch, sampling every file — used for the synchronous initial scan only;
+/// the fallback heartbeat rescan walks the tree in `spawn_source_scan` instead. Incremental
+/// updates after that go through `analyze_source_file` for just the files a watch event touched.
+fn scan_source(path: &str) -> FileCache {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let key = e.path().to_string_lossy().to_string();
+            analyze_source_file(e.path()).map(|v| (key, v))
+        })
+        .collect()
+}
 
 struct Source {
     name: String,
@@ -17,93 +129,451 @@ struct Source {
     entropy: f64,
     files: usize,
     status: &'static str,
-    last_scan: String,
+    last_scan_at: Instant,
     history: Vec<f64>,
+    file_cache: FileCache,
+    total_bytes: u64,
+    prev_files: Option<usize>,
+    prev_total_bytes: Option<u64>,
+    /// Set once the first real scan lands, so that scan doesn't get diffed against the
+    /// zeroed-out placeholder state `App::new` constructs sources with.
+    scanned_once: bool,
+    /// `Some((done, total))` while a background rescan worker is walking this source's tree —
+    /// drives the STATUS column's progress indicator. `None` when idle.
+    scan_progress: Option<(usize, usize)>,
 }
 
 struct App {
     sources: Vec<Source>,
     selected: usize,
-    alerts: Vec<String>,
+    alerts: Vec<Diagnostic>,
     tick: u64,
+    rules: Vec<Box<dyn rules::Rule>>,
+    /// `None` when no `[restic]` repository is configured — the timeline pane then shows a
+    /// hint instead of an empty widget. `Arc`-wrapped so a background refresh task can hold
+    /// its own handle without borrowing `App`.
+    restic_runner: Option<Arc<ResticRunner>>,
+    restic_snapshots: Option<Vec<Snapshot>>,
+    /// From `ResticConfig::expected_interval_hours`; a gap between snapshots wider than this
+    /// is drawn as a missed-backup marker on the timeline.
+    restic_expected_interval_hours: f64,
+    /// `Some` while the drill-down file inspector is open, replacing the normal dashboard view.
+    inspector: Option<Inspector>,
+}
+
+/// One file within an inspected source, sorted by closeness to 8.0 bits/byte — the ideal
+/// entropy of encrypted data — so the files most worth eyeballing sort to the top.
+struct InspectorFile {
+    path: String,
+    size: u64,
+    entropy: f64,
+}
+
+/// A loaded preview: either a hex+ASCII dump (binary/encrypted-looking content) or
+/// syntax-highlighted lines (text), plus the footer metadata for whichever file is open.
+struct Preview {
+    hex: Option<Vec<String>>,
+    text: Option<Vec<Vec<(syntect::highlighting::Style, String)>>>,
+    meta: Option<FileMeta>,
+}
+
+/// Drill-down state opened by pressing Enter on a selected `Source`: a list of its
+/// highest-entropy files, and optionally a loaded preview of whichever one is selected.
+struct Inspector {
+    source_name: String,
+    files: Vec<InspectorFile>,
+    selected: usize,
+    preview: Option<Preview>,
+}
+
+impl Inspector {
+    /// List `source`'s files nearest to 8.0 bits/byte — the files an operator triaging an
+    /// entropy-spike alert actually needs to look at.
+    fn open(source: &Source) -> Self {
+        let mut files: Vec<InspectorFile> = source
+            .file_cache
+            .iter()
+            .map(|(path, &(size, entropy))| InspectorFile {
+                path: path.clone(),
+                size,
+                entropy,
+            })
+            .collect();
+        files.sort_by(|a, b| {
+            (8.0 - a.entropy)
+                .abs()
+                .partial_cmp(&(8.0 - b.entropy).abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        Inspector {
+            source_name: source.name.clone(),
+            files,
+            selected: 0,
+            preview: None,
+        }
+    }
+
+    /// Load a preview of the currently-selected file: a hex dump if it looks binary/encrypted,
+    /// a syntax-highlighted view if it looks like text. Read failures (permissions, file
+    /// removed since the scan) just leave the preview empty instead of crashing the dashboard.
+    fn load_preview(&mut self) {
+        let Some(file) = self.files.get(self.selected) else {
+            return;
+        };
+        let path = Path::new(&file.path);
+        let meta = preview::file_meta(path).ok();
+
+        let Ok(bytes) = fs::read(path) else {
+            self.preview = Some(Preview { hex: None, text: None, meta });
+            return;
+        };
+
+        if preview::looks_like_text(&bytes[..bytes.len().min(SAMPLE_SIZE as usize)]) {
+            if let Ok(contents) = String::from_utf8(bytes) {
+                self.preview = Some(Preview {
+                    hex: None,
+                    text: Some(preview::highlight_source(path, &contents)),
+                    meta,
+                });
+                return;
+            }
+        }
+
+        self.preview = Some(Preview {
+            hex: Some(preview::hex_dump(&bytes)),
+            text: None,
+            meta,
+        });
+    }
+}
+
+impl Source {
+    fn new(name: &str, path: &str) -> Self {
+        Source {
+            name: name.into(),
+            path: path.into(),
+            entropy: 0.0,
+            files: 0,
+            status: "✅ OK",
+            last_scan_at: Instant::now(),
+            history: Vec::new(),
+            file_cache: FileCache::new(),
+            total_bytes: 0,
+            prev_files: None,
+            prev_total_bytes: None,
+            scanned_once: false,
+            scan_progress: None,
+        }
+    }
 }
 
 impl App {
     fn new() -> Self {
-        App {
-            sources: vec![
-                Source {
-                    name: "cthonian".into(),
-                    path: "/home".into(),
-                    entropy: 4.85,
-                    files: 67197,
-                    status: "✅ OK",
-                    last_scan: "2m ago".into(),
-                    history: vec![4.82, 4.83, 4.85, 4.84, 4.85, 4.83, 4.84, 4.85, 4.86, 4.85,
-                                  4.84, 4.85, 4.83, 4.85, 4.84, 4.85, 4.86, 4.85, 4.84, 4.85],
-                },
-                Source {
-                    name: "yogsothoth".into(),
-                    path: "wdp10".into(),
-                    entropy: 7.96,
-                    files: 295,
-                    status: "✅ OK",
-                    last_scan: "5m ago".into(),
-                    history: vec![7.95, 7.96, 7.96, 7.95, 7.96, 7.96, 7.95, 7.96, 7.96, 7.95,
-                                  7.96, 7.96, 7.95, 7.96, 7.96, 7.95, 7.96, 7.96, 7.95, 7.96],
-                },
-                Source {
-                    name: "yogsothoth".into(),
-                    path: "clawd-backup".into(),
-                    entropy: 4.85,
-                    files: 66991,
-                    status: "✅ OK",
-                    last_scan: "5m ago".into(),
-                    history: vec![4.84, 4.85, 4.85, 4.84, 4.85, 4.85, 4.84, 4.85, 4.85, 4.84,
-                                  4.85, 4.85, 4.84, 4.85, 4.85, 4.84, 4.85, 4.85, 4.84, 4.85],
-                },
-                Source {
-                    name: "gertrude".into(),
-                    path: "/home".into(),
-                    entropy: 5.04,
-                    files: 146,
-                    status: "✅ OK",
-                    last_scan: "8m ago".into(),
-                    history: vec![5.02, 5.03, 5.04, 5.03, 5.04, 5.03, 5.04, 5.04, 5.03, 5.04,
-                                  5.03, 5.04, 5.04, 5.03, 5.04, 5.03, 5.04, 5.04, 5.03, 5.04],
-                },
-            ],
+        let mut sources = vec![
+            Source::new("cthonian", "/home"),
+            Source::new("yogsothoth", "wdp10"),
+            Source::new("yogsothoth", "clawd-backup"),
+            Source::new("gertrude", "/home"),
+        ];
+
+        for source in sources.iter_mut() {
+            rescan_source(source);
+        }
+
+        let config = ExarpConfig::load().unwrap_or_default();
+        let rules = rules::default_rules(&config.alerts);
+        let restic_runner = config
+            .restic
+            .repository
+            .is_some()
+            .then(|| Arc::new(ResticRunner::from_config(&config)));
+        let restic_expected_interval_hours =
+            config.restic.expected_interval_hours.unwrap_or(24) as f64;
+
+        let mut app = App {
+            sources,
             selected: 0,
-            alerts: vec!["No alerts. The Watchtower sees all is well.".into()],
+            alerts: Vec::new(),
             tick: 0,
+            rules,
+            restic_runner,
+            restic_snapshots: None,
+            restic_expected_interval_hours,
+            inspector: None,
+        };
+        app.refresh_restic_snapshots();
+        app.evaluate_rules();
+        app
+    }
+
+    /// Re-list snapshots from the configured restic repository. Shells out synchronously, so
+    /// this is only called once from `App::new`; the periodic `RESTIC_REFRESH_RATE` refresh
+    /// goes through `spawn_restic_refresh` instead. A failed run (no
+    /// repo reachable, wrong password, restic not installed) just leaves the last good list
+    /// in place instead of blanking the timeline.
+    fn refresh_restic_snapshots(&mut self) {
+        let Some(runner) = &self.restic_runner else {
+            return;
+        };
+        if let Ok(snapshots) = runner.snapshots() {
+            self.restic_snapshots = Some(snapshots);
         }
     }
 
-    fn simulate_tick(&mut self) {
+    /// Open the drill-down inspector on the selected source.
+    fn open_inspector(&mut self) {
+        self.inspector = Some(Inspector::open(&self.sources[self.selected]));
+    }
+
+    fn close_inspector(&mut self) {
+        self.inspector = None;
+    }
+
+    fn inspector_move(&mut self, delta: i64) {
+        if let Some(inspector) = &mut self.inspector {
+            let len = inspector.files.len();
+            if len == 0 {
+                return;
+            }
+            let next = inspector.selected as i64 + delta;
+            inspector.selected = next.clamp(0, len as i64 - 1) as usize;
+        }
+    }
+
+    fn inspector_load_preview(&mut self) {
+        if let Some(inspector) = &mut self.inspector {
+            inspector.load_preview();
+        }
+    }
+
+    /// Esc: close a loaded preview back to the file list, or close the inspector entirely if
+    /// no preview is open — one level of "back" per keypress.
+    fn inspector_back(&mut self) {
+        let has_preview = self.inspector.as_ref().is_some_and(|i| i.preview.is_some());
+        if has_preview {
+            if let Some(inspector) = &mut self.inspector {
+                inspector.preview = None;
+            }
+        } else {
+            self.inspector = None;
+        }
+    }
+
+    /// Re-run every rule across all sources and refresh `alerts`, worst-first.
+    fn evaluate_rules(&mut self) {
+        self.alerts = self
+            .sources
+            .iter()
+            .flat_map(|s| {
+                let ctx = RuleContext {
+                    name: &s.name,
+                    entropy_history: &s.history,
+                    files: s.files,
+                    prev_files: s.prev_files,
+                    total_bytes: s.total_bytes,
+                    prev_total_bytes: s.prev_total_bytes,
+                    hours_since_scan: s.last_scan_at.elapsed().as_secs_f64() / 3600.0,
+                };
+                rules::run_rules(&self.rules, &ctx)
+            })
+            .collect();
+        self.alerts.sort_by(|a, b| b.severity.cmp(&a.severity));
+    }
+
+    /// Advance the spinner. Runs every `tick_rate`; the real entropy updates now come from
+    /// `apply_touched`/background rescan workers instead of being faked here.
+    fn tick_spinner(&mut self) {
         self.tick += 1;
-        // Each source gets unique jitter pattern
-        for (i, source) in self.sources.iter_mut().enumerate() {
-            let base = source.entropy;
-            let phase = (i as f64) * 1.7; // phase offset per source
-            let freq = 0.08 + (i as f64) * 0.03; // different frequencies
-            let jitter = ((self.tick as f64 * freq + phase).sin() * 0.08) + 
-                         ((self.tick as f64 * freq * 2.3 + phase).cos() * 0.04);
-            let new_val = base + jitter;
-            source.history.push(new_val);
-            if source.history.len() > 60 {
-                source.history.remove(0);
+    }
+
+    /// Kick off a background rescan of `idx` on the tokio blocking pool instead of walking the
+    /// tree here, so a 67k-file source never stalls the render/input loop. A no-op if that
+    /// source already has a rescan in flight. `scan_progress` is set to a zeroed placeholder
+    /// immediately so the STATUS column shows motion before the worker's first progress report
+    /// arrives, and so a second trigger (heartbeat racing a manual `r`) doesn't double-spawn.
+    fn trigger_rescan(&mut self, idx: usize, tx: &tokio_mpsc::UnboundedSender<WorkerMsg>) {
+        let Some(source) = self.sources.get_mut(idx) else {
+            return;
+        };
+        if source.scan_progress.is_some() {
+            return;
+        }
+        source.scan_progress = Some((0, 0));
+        spawn_source_scan(idx, source.path.clone(), tx.clone());
+    }
+
+    /// Fallback heartbeat: a safety net for changes a watcher missed (e.g. a path that didn't
+    /// exist yet when watches were registered), fully rescanning every idle source. Runs on a
+    /// much longer cadence than the spinner since it re-walks whole trees.
+    fn heartbeat_rescan(&mut self, tx: &tokio_mpsc::UnboundedSender<WorkerMsg>) {
+        for idx in 0..self.sources.len() {
+            self.trigger_rescan(idx, tx);
+        }
+    }
+
+    /// Fold a batch of watcher-touched paths into the owning source's aggregate without
+    /// re-walking the rest of its tree — the incremental path `Watch` uses in main.rs.
+    fn apply_touched(&mut self, touched: &HashSet<PathBuf>) {
+        for source in self.sources.iter_mut() {
+            let mut changed = false;
+            for p in touched {
+                if !p.starts_with(&source.path) {
+                    continue;
+                }
+                let key = p.to_string_lossy().to_string();
+                match analyze_source_file(p) {
+                    Some(entry) => {
+                        source.file_cache.insert(key, entry);
+                    }
+                    None => {
+                        source.file_cache.remove(&key);
+                    }
+                }
+                changed = true;
+            }
+            if changed {
+                apply_scan_update(source);
             }
         }
     }
 }
 
+/// Common bookkeeping after a source's `file_cache` has been refreshed (whether by a full
+/// walk or folding in a handful of touched files): recompute the aggregate, snapshot the
+/// previous files/bytes for the drift rules to diff against, and push the new history point.
+fn apply_scan_update(source: &mut Source) {
+    if source.scanned_once {
+        source.prev_files = Some(source.files);
+        source.prev_total_bytes = Some(source.total_bytes);
+    }
+    source.scanned_once = true;
+
+    source.entropy = aggregate_cache(&source.file_cache);
+    source.files = source.file_cache.len();
+    source.total_bytes = total_bytes(&source.file_cache);
+    source.last_scan_at = Instant::now();
+    source.history.push(source.entropy);
+    if source.history.len() > 60 {
+        source.history.remove(0);
+    }
+}
+
+/// Fully rescan a source's path and push the real weighted entropy into its history,
+/// replacing the sine-wave jitter this used to fabricate. Synchronous — only used for the
+/// one-time startup scan in `App::new`, before the render/input loop (and its progress
+/// indicator) even exists. Runtime rescans go through `spawn_source_scan` instead.
+fn rescan_source(source: &mut Source) {
+    source.file_cache = scan_source(&source.path);
+    apply_scan_update(source);
+}
+
+/// Posted from background worker tasks back to the render/input loop over an unbounded async
+/// channel, so a full-tree rescan or a restic shell-out never blocks drawing a frame or
+/// reading a keypress.
+enum WorkerMsg {
+    /// A source's rescan has walked `done` of an estimated `total` files so far — feeds the
+    /// STATUS column's progress indicator.
+    ScanProgress { source: usize, done: usize, total: usize },
+    /// A source's rescan finished; replace its cache and recompute the aggregate.
+    ScanComplete { source: usize, cache: FileCache },
+    /// A settled batch of filesystem-watcher events, forwarded from the blocking `notify`
+    /// callback after the same debounce window the old sync loop used.
+    FsEvents(HashSet<PathBuf>),
+    /// Fresh snapshot list from a background restic query. Failures are simply dropped,
+    /// matching `refresh_restic_snapshots`'s "leave the last good list" behavior.
+    ResticSnapshots(Vec<Snapshot>),
+}
+
+/// Walk `path` on the blocking thread pool, sampling each file and reporting progress back
+/// over `tx` every 100ms (and on the final file) instead of only posting a result at the end —
+/// a 67k-file source can take long enough that an operator needs to see motion, not a frozen
+/// percentage.
+fn spawn_source_scan(source: usize, path: String, tx: tokio_mpsc::UnboundedSender<WorkerMsg>) {
+    tokio::task::spawn_blocking(move || {
+        let entries: Vec<PathBuf> = WalkDir::new(&path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .collect();
+        let total = entries.len();
+
+        let mut cache = FileCache::new();
+        let mut last_report = Instant::now();
+        for (i, fpath) in entries.iter().enumerate() {
+            let key = fpath.to_string_lossy().to_string();
+            if let Some(entry) = analyze_source_file(fpath) {
+                cache.insert(key, entry);
+            }
+            let done = i + 1;
+            if last_report.elapsed() >= Duration::from_millis(100) || done == total {
+                let _ = tx.send(WorkerMsg::ScanProgress { source, done, total });
+                last_report = Instant::now();
+            }
+        }
+
+        let _ = tx.send(WorkerMsg::ScanComplete { source, cache });
+    });
+}
+
+/// Query the configured restic repository on the blocking thread pool and post the result back.
+/// Shelling out is the other operation (besides a full-tree walk) that used to stall the old
+/// sync loop's whole tick.
+fn spawn_restic_refresh(runner: Arc<ResticRunner>, tx: tokio_mpsc::UnboundedSender<WorkerMsg>) {
+    tokio::task::spawn_blocking(move || {
+        if let Ok(snapshots) = runner.snapshots() {
+            let _ = tx.send(WorkerMsg::ResticSnapshots(snapshots));
+        }
+    });
+}
+
+/// Forward filesystem-watcher events onto a dedicated OS thread: block on the `notify`
+/// callback's std channel, debounce a burst into one batch the same way the old sync loop did,
+/// and post the settled batch as a `WorkerMsg` so the async loop never polls it.
+fn spawn_fs_forwarder(rx: std_mpsc::Receiver<notify::Event>, tx: tokio_mpsc::UnboundedSender<WorkerMsg>) {
+    std::thread::spawn(move || loop {
+        let mut touched: HashSet<PathBuf> = HashSet::new();
+        match rx.recv() {
+            Ok(event) => touched.extend(event.paths),
+            Err(_) => return,
+        }
+        loop {
+            match rx.recv_timeout(WATCH_DEBOUNCE) {
+                Ok(event) => touched.extend(event.paths),
+                Err(std_mpsc::RecvTimeoutError::Timeout) => break,
+                Err(std_mpsc::RecvTimeoutError::Disconnected) => return,
+            }
+        }
+        if tx.send(WorkerMsg::FsEvents(touched)).is_err() {
+            return;
+        }
+    });
+}
+
+/// Render a "Xs/Xm ago" label for the table's LAST column — recomputed every frame so the
+/// age visibly climbs between scans even when nothing has changed.
+fn format_age(last_scan_at: Instant) -> String {
+    let secs = last_scan_at.elapsed().as_secs();
+    if secs < 60 {
+        format!("{}s ago", secs)
+    } else {
+        format!("{}m ago", secs / 60)
+    }
+}
+
 fn render_ui(frame: &mut Frame, app: &App) {
+    if let Some(inspector) = &app.inspector {
+        render_inspector(frame, inspector);
+        return;
+    }
+
     let main_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),  // Title
             Constraint::Length(8),  // Sources table
-            Constraint::Min(10),   // Graph
+            Constraint::Min(6),    // Graph
+            Constraint::Length(5), // Restic snapshot timeline
             Constraint::Length(5), // Alerts
             Constraint::Length(3), // Disk + footer
         ])
@@ -150,13 +620,22 @@ fn render_ui(frame: &mut Frame, app: &App) {
             Style::default().fg(Color::Green)
         };
 
+        let status_cell = match s.scan_progress {
+            Some((done, total)) if total > 0 => {
+                Cell::from(format!("⏳{:>3}%", (done * 100 / total).min(100)))
+                    .style(Style::default().fg(Color::Yellow))
+            }
+            Some(_) => Cell::from("⏳ ...").style(Style::default().fg(Color::Yellow)),
+            None => Cell::from(s.status),
+        };
+
         Row::new(vec![
             Cell::from(s.name.clone()).style(style),
             Cell::from(s.path.clone()).style(style),
             Cell::from(format!("{:.2} b/B", s.entropy)).style(entropy_style),
             Cell::from(format!("{:>6}", s.files)).style(style),
-            Cell::from(s.status),
-            Cell::from(s.last_scan.clone()).style(Style::default().fg(Color::DarkGray)),
+            status_cell,
+            Cell::from(format_age(s.last_scan_at)).style(Style::default().fg(Color::DarkGray)),
         ])
     }).collect();
 
@@ -237,13 +716,27 @@ fn render_ui(frame: &mut Frame, app: &App) {
         );
     frame.render_widget(chart, main_layout[2]);
 
-    // Alerts
-    let alert_text: Vec<Line> = app.alerts.iter().map(|a| {
-        Line::from(Span::styled(
-            format!("  {}", a),
+    render_restic_timeline(frame, main_layout[3], app);
+
+    // Alerts — worst severity first, colored green/yellow/red
+    let alert_text: Vec<Line> = if app.alerts.is_empty() {
+        vec![Line::from(Span::styled(
+            "  No alerts. The Watchtower sees all is well.",
             Style::default().fg(Color::Green),
-        ))
-    }).collect();
+        ))]
+    } else {
+        app.alerts
+            .iter()
+            .map(|d| {
+                let color = match d.severity {
+                    Severity::Info => Color::Green,
+                    Severity::Warn => Color::Yellow,
+                    Severity::Critical => Color::Red,
+                };
+                Line::from(Span::styled(format!("  {}", d.message), Style::default().fg(color)))
+            })
+            .collect()
+    };
 
     let alerts = Paragraph::new(alert_text)
         .block(
@@ -252,7 +745,7 @@ fn render_ui(frame: &mut Frame, app: &App) {
                 .border_style(Style::default().fg(Color::Cyan))
                 .title(" Alerts "),
         );
-    frame.render_widget(alerts, main_layout[3]);
+    frame.render_widget(alerts, main_layout[4]);
 
     // Footer with disk bars and help
     let footer = Paragraph::new(Line::from(vec![
@@ -273,46 +766,364 @@ fn render_ui(frame: &mut Frame, app: &App) {
             .borders(Borders::ALL)
             .border_style(Style::default().fg(Color::Cyan)),
     );
-    frame.render_widget(footer, main_layout[4]);
+    frame.render_widget(footer, main_layout[5]);
+}
+
+/// Render the restic snapshot timeline: a ruler spanning oldest-snapshot → now, with a dot
+/// per snapshot and a gap marker wherever the interval between two consecutive snapshots
+/// exceeds `restic_expected_interval_hours` — turns that config field from a dead number into
+/// a visible missed-backup signal, correlated against the same timeline as the entropy chart.
+fn render_restic_timeline(frame: &mut Frame, area: Rect, app: &App) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Restic Snapshots ");
+
+    let Some(snapshots) = &app.restic_snapshots else {
+        let msg = if app.restic_runner.is_some() {
+            "  Could not list snapshots from the configured repository"
+        } else {
+            "  No [restic] repository configured — set restic.repository in ~/.exarp/config.toml"
+        };
+        let hint = Paragraph::new(msg)
+        .style(Style::default().fg(Color::DarkGray))
+        .block(block);
+        frame.render_widget(hint, area);
+        return;
+    };
+
+    if snapshots.is_empty() {
+        let hint = Paragraph::new("  No snapshots found in the configured repository")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(block);
+        frame.render_widget(hint, area);
+        return;
+    }
+
+    let mut times: Vec<DateTime<Utc>> = snapshots
+        .iter()
+        .filter_map(|s| DateTime::parse_from_rfc3339(&s.time).ok())
+        .map(|t| t.with_timezone(&Utc))
+        .collect();
+    times.sort();
+
+    if times.is_empty() {
+        let hint = Paragraph::new("  Could not parse snapshot timestamps")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(block);
+        frame.render_widget(hint, area);
+        return;
+    }
+
+    let inner_width = area.width.saturating_sub(2).max(1) as usize;
+    let oldest = *times.first().unwrap();
+    let now = Utc::now();
+    let span_secs = (now - oldest).num_seconds().max(1) as f64;
+
+    let pos_of = |t: DateTime<Utc>| -> usize {
+        let frac = ((t - oldest).num_seconds() as f64 / span_secs).clamp(0.0, 1.0);
+        ((frac * (inner_width - 1) as f64).round() as usize).min(inner_width - 1)
+    };
+
+    let mut ruler = vec!['─'; inner_width];
+    let mut gaps = 0usize;
+    for pair in times.windows(2) {
+        let gap_hours = (pair[1] - pair[0]).num_seconds() as f64 / 3600.0;
+        if gap_hours > app.restic_expected_interval_hours {
+            gaps += 1;
+            let mid = pair[0] + (pair[1] - pair[0]) / 2;
+            ruler[pos_of(mid)] = '⚠';
+        }
+    }
+    for &t in &times {
+        ruler[pos_of(t)] = '●';
+    }
+
+    let ruler_line: Vec<Span> = ruler
+        .into_iter()
+        .map(|c| match c {
+            '●' => Span::styled("●", Style::default().fg(Color::Green)),
+            '⚠' => Span::styled("⚠", Style::default().fg(Color::Red)),
+            _ => Span::styled("─", Style::default().fg(Color::DarkGray)),
+        })
+        .collect();
+
+    let latest = times.last().unwrap();
+    let hours_since_latest = (now - *latest).num_seconds() as f64 / 3600.0;
+    let summary = format!(
+        "  {} snapshots, oldest {} ago, latest {:.0}h ago{}",
+        times.len(),
+        format_duration_hours((now - oldest).num_seconds() as f64 / 3600.0),
+        hours_since_latest,
+        if gaps > 0 {
+            format!(" — {} gap(s) over {:.0}h threshold", gaps, app.restic_expected_interval_hours)
+        } else {
+            String::new()
+        },
+    );
+    let summary_style = if gaps > 0 {
+        Style::default().fg(Color::Red)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+
+    let body = vec![Line::from(ruler_line), Line::from(Span::styled(summary, summary_style))];
+    frame.render_widget(Paragraph::new(body).block(block), area);
+}
+
+/// Render an hour count as "Xd" once it crosses a day, otherwise "Xh" — used by the restic
+/// timeline summary line.
+fn format_duration_hours(hours: f64) -> String {
+    if hours >= 24.0 {
+        format!("{:.0}d", hours / 24.0)
+    } else {
+        format!("{:.0}h", hours)
+    }
+}
+
+/// Render the drill-down inspector: a file list (nearest 8.0 bits/byte first) on the left, a
+/// hex/syntax preview of the selected file on the right, and a permissions/owner/mtime footer.
+/// Replaces the whole normal dashboard view while open.
+fn render_inspector(frame: &mut Frame, inspector: &Inspector) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(10), Constraint::Length(3)])
+        .split(frame.area());
+
+    let title = Paragraph::new(Line::from(vec![Span::styled(
+        format!("  🔍 Inspecting {} — ↑↓ select, Enter preview, Esc back", inspector.source_name),
+        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+    )]))
+    .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Cyan)));
+    frame.render_widget(title, layout[0]);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(46), Constraint::Min(20)])
+        .split(layout[1]);
+
+    let rows: Vec<Row> = inspector
+        .files
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            let style = if i == inspector.selected {
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            let entropy_style = if f.entropy > 7.5 {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default().fg(Color::Green)
+            };
+            Row::new(vec![
+                Cell::from(format!("{:.2}", f.entropy)).style(entropy_style),
+                Cell::from(format!("{:>10}", f.size)).style(style),
+                Cell::from(f.path.clone()).style(style),
+            ])
+        })
+        .collect();
+
+    let file_list = Table::new(
+        rows,
+        [Constraint::Length(6), Constraint::Length(10), Constraint::Min(10)],
+    )
+    .header(
+        Row::new(vec!["ENT", "SIZE", "PATH"])
+            .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+    )
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(" Highest-entropy files "),
+    );
+    frame.render_widget(file_list, columns[0]);
+
+    let preview_block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(" Preview ");
+
+    let preview_lines: Vec<Line> = match &inspector.preview {
+        None => vec![Line::from(Span::styled(
+            "  Press Enter to preview the selected file",
+            Style::default().fg(Color::DarkGray),
+        ))],
+        Some(preview) => {
+            if let Some(hex) = &preview.hex {
+                hex.iter()
+                    .map(|line| Line::from(Span::styled(line.clone(), Style::default().fg(Color::Green))))
+                    .collect()
+            } else if let Some(text) = &preview.text {
+                text.iter()
+                    .map(|runs| {
+                        Line::from(
+                            runs.iter()
+                                .map(|(style, text)| {
+                                    let fg = Color::Rgb(
+                                        style.foreground.r,
+                                        style.foreground.g,
+                                        style.foreground.b,
+                                    );
+                                    Span::styled(text.clone(), Style::default().fg(fg))
+                                })
+                                .collect::<Vec<_>>(),
+                        )
+                    })
+                    .collect()
+            } else {
+                vec![Line::from(Span::styled(
+                    "  Could not read this file",
+                    Style::default().fg(Color::Red),
+                ))]
+            }
+        }
+    };
+    frame.render_widget(Paragraph::new(preview_lines).block(preview_block), columns[1]);
+
+    let footer_text = match inspector.preview.as_ref().and_then(|p| p.meta.as_ref()) {
+        Some(meta) => format!(
+            "  {}  uid={}  mtime={}",
+            meta.permissions,
+            meta.owner_uid,
+            meta.mtime.format("%Y-%m-%d %H:%M:%S UTC"),
+        ),
+        None => "  (no file loaded)".to_string(),
+    };
+    let footer = Paragraph::new(Line::from(Span::styled(footer_text, Style::default().fg(Color::DarkGray)))).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    frame.render_widget(footer, layout[2]);
 }
 
+/// Full heartbeat rescans are expensive (they re-walk a whole source tree), so they run on a
+/// much longer cadence than the spinner — real-time updates come from the watcher instead.
+const HEARTBEAT_RATE: Duration = Duration::from_secs(10);
+
+/// Listing snapshots shells out to restic, so it runs on its own, even slower cadence than
+/// the heartbeat rescan.
+const RESTIC_REFRESH_RATE: Duration = Duration::from_secs(30);
+
+/// Entry point kept synchronous so `main.rs` doesn't need to know the dashboard runs on tokio
+/// internally — it just builds its own single-threaded runtime and blocks on the async loop.
 pub fn run_dashboard() -> Result<()> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()?;
+    runtime.block_on(run_dashboard_async())
+}
+
+/// Async event-driven replacement for the old synchronous poll-and-rescan loop. Input is read
+/// from crossterm's `EventStream` instead of blocking `event::read()`, and every operation that
+/// used to stall a whole tick — a full-tree rescan, a restic shell-out — now runs on the tokio
+/// blocking pool and reports back over `WorkerMsg`, so rendering and keypresses stay responsive
+/// even while a 67k-file source is mid-scan.
+async fn run_dashboard_async() -> Result<()> {
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
 
     let mut app = App::new();
+    let (worker_tx, mut worker_rx) = tokio_mpsc::unbounded_channel::<WorkerMsg>();
+
+    let (fs_tx, fs_rx) = std_mpsc::channel();
+    let mut watcher: RecommendedWatcher = notify::recommended_watcher(move |res| {
+        if let Ok(event) = res {
+            let _ = fs_tx.send(event);
+        }
+    })?;
+    for source in &app.sources {
+        // A demo source's path may not exist on this machine — a failed watch shouldn't
+        // take down the whole dashboard, just leave that source on the heartbeat fallback.
+        let _ = watcher.watch(Path::new(&source.path), RecursiveMode::Recursive);
+    }
+    spawn_fs_forwarder(fs_rx, worker_tx.clone());
+
+    let mut events = event::EventStream::new();
     let tick_rate = Duration::from_millis(250);
-    let mut last_tick = Instant::now();
+    let mut tick_interval = tokio::time::interval(tick_rate);
+    let mut heartbeat_interval = tokio::time::interval(HEARTBEAT_RATE);
+    let mut restic_interval = tokio::time::interval(RESTIC_REFRESH_RATE);
 
     loop {
         terminal.draw(|frame| render_ui(frame, &app))?;
 
-        let timeout = tick_rate.saturating_sub(last_tick.elapsed());
-        if event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => break,
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            if app.selected > 0 {
-                                app.selected -= 1;
-                            }
+        tokio::select! {
+            maybe_event = events.next() => {
+                let Some(event) = maybe_event else { break };
+                if let Event::Key(key) = event? {
+                    if key.kind == KeyEventKind::Press && app.inspector.is_some() {
+                        match key.code {
+                            KeyCode::Char('q') => break,
+                            KeyCode::Esc => app.inspector_back(),
+                            KeyCode::Up | KeyCode::Char('k') => app.inspector_move(-1),
+                            KeyCode::Down | KeyCode::Char('j') => app.inspector_move(1),
+                            KeyCode::Enter => app.inspector_load_preview(),
+                            _ => {}
                         }
-                        KeyCode::Down | KeyCode::Char('j') => {
-                            if app.selected < app.sources.len() - 1 {
-                                app.selected += 1;
+                    } else if key.kind == KeyEventKind::Press {
+                        match key.code {
+                            KeyCode::Char('q') | KeyCode::Esc => break,
+                            KeyCode::Up | KeyCode::Char('k') => {
+                                if app.selected > 0 {
+                                    app.selected -= 1;
+                                }
+                            }
+                            KeyCode::Down | KeyCode::Char('j') => {
+                                if app.selected < app.sources.len() - 1 {
+                                    app.selected += 1;
+                                }
                             }
+                            KeyCode::Char('r') => app.trigger_rescan(app.selected, &worker_tx),
+                            KeyCode::Enter => app.open_inspector(),
+                            _ => {}
                         }
-                        _ => {}
                     }
                 }
             }
-        }
 
-        if last_tick.elapsed() >= tick_rate {
-            app.simulate_tick();
-            last_tick = Instant::now();
+            Some(msg) = worker_rx.recv() => {
+                match msg {
+                    WorkerMsg::ScanProgress { source, done, total } => {
+                        if let Some(src) = app.sources.get_mut(source) {
+                            src.scan_progress = Some((done, total));
+                        }
+                    }
+                    WorkerMsg::ScanComplete { source, cache } => {
+                        if let Some(src) = app.sources.get_mut(source) {
+                            src.file_cache = cache;
+                            src.scan_progress = None;
+                            apply_scan_update(src);
+                        }
+                        app.evaluate_rules();
+                    }
+                    WorkerMsg::FsEvents(touched) => {
+                        app.apply_touched(&touched);
+                        app.evaluate_rules();
+                    }
+                    WorkerMsg::ResticSnapshots(snapshots) => {
+                        app.restic_snapshots = Some(snapshots);
+                    }
+                }
+            }
+
+            _ = tick_interval.tick() => {
+                app.tick_spinner();
+            }
+
+            _ = heartbeat_interval.tick() => {
+                app.heartbeat_rescan(&worker_tx);
+            }
+
+            _ = restic_interval.tick() => {
+                if let Some(runner) = &app.restic_runner {
+                    spawn_restic_refresh(Arc::clone(runner), worker_tx.clone());
+                }
+            }
         }
     }
 
@@ -320,3 +1131,161 @@ pub fn run_dashboard() -> Result<()> {
     stdout().execute(LeaveAlternateScreen)?;
     Ok(())
 }
+
+/// Golden/snapshot tests for `render_ui`, modeled on the record-and-replay ref-tests terminal
+/// emulators use: a synthetic `App` is put into a scripted state (the selection is set directly
+/// on `App`; no key events are fed through the input loop) and advanced by spinner ticks, each
+/// frame renders to a `TestBackend`, and the resulting cell grid (text + styled runs) is
+/// compared against a checked-in golden file. Regenerate with
+/// `EXARP_UPDATE_GOLDEN=1 cargo test --package exarp dashboard::tests`.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AlertConfig;
+    use ratatui::backend::TestBackend;
+    use ratatui::buffer::Buffer;
+    use std::path::PathBuf;
+
+    const GOLDEN_WIDTH: u16 = 100;
+    const GOLDEN_HEIGHT: u16 = 30;
+
+    /// A fully synthetic, zero-I/O `App` — no config file, no real directory walk — so the
+    /// golden output only depends on the state set here, never on what the sandbox's
+    /// filesystem happens to contain.
+    fn harness_app() -> App {
+        let mut cthonian = Source::new("cthonian", "/home");
+        cthonian.entropy = 4.21;
+        cthonian.files = 128;
+        cthonian.history = vec![3.9, 4.0, 4.05, 4.21];
+        cthonian.total_bytes = 4_096_000;
+        cthonian.prev_files = Some(126);
+        cthonian.prev_total_bytes = Some(4_090_000);
+        cthonian.scanned_once = true;
+
+        let mut yogsothoth = Source::new("yogsothoth", "wdp10");
+        yogsothoth.entropy = 7.95;
+        yogsothoth.files = 64;
+        yogsothoth.history = vec![5.0, 6.2, 7.95];
+        yogsothoth.total_bytes = 2_048_000;
+        yogsothoth.prev_files = Some(60);
+        yogsothoth.prev_total_bytes = Some(2_048_000);
+        yogsothoth.scanned_once = true;
+
+        let config = AlertConfig::default();
+        let rules = rules::default_rules(&config);
+        let mut app = App {
+            sources: vec![cthonian, yogsothoth],
+            selected: 0,
+            alerts: Vec::new(),
+            tick: 0,
+            rules,
+            restic_runner: None,
+            restic_snapshots: None,
+            restic_expected_interval_hours: 24.0,
+            inspector: None,
+        };
+        app.evaluate_rules();
+        app
+    }
+
+    /// Render `app` to a fixed-size `TestBackend` and dump the resulting buffer.
+    fn render_to_string(app: &App) -> String {
+        let backend = TestBackend::new(GOLDEN_WIDTH, GOLDEN_HEIGHT);
+        let mut terminal = Terminal::new(backend).expect("test backend terminal");
+        terminal
+            .draw(|frame| render_ui(frame, app))
+            .expect("render_ui");
+        dump_buffer(terminal.backend().buffer())
+    }
+
+    /// Serialize a buffer's text and styling into a string stable enough to diff in a golden
+    /// file: one line of raw characters per row, followed by that row's non-default styled
+    /// runs as `[start..end] fg=.. bg=.. mod=..`.
+    fn dump_buffer(buffer: &Buffer) -> String {
+        let mut out = String::new();
+        for y in 0..buffer.area.height {
+            let mut row = String::new();
+            for x in 0..buffer.area.width {
+                row.push_str(buffer.get(x, y).symbol());
+            }
+            out.push_str(row.trim_end());
+            out.push('\n');
+
+            let mut x = 0;
+            while x < buffer.area.width {
+                let cell = buffer.get(x, y);
+                let style = (cell.fg, cell.bg, cell.modifier);
+                let start = x;
+                while x < buffer.area.width && {
+                    let c = buffer.get(x, y);
+                    (c.fg, c.bg, c.modifier) == style
+                } {
+                    x += 1;
+                }
+                if style != (Color::Reset, Color::Reset, Modifier::empty()) {
+                    out.push_str(&format!(
+                        "  [{}..{}] fg={:?} bg={:?} mod={:?}\n",
+                        start, x, style.0, style.1, style.2
+                    ));
+                }
+            }
+        }
+        out
+    }
+
+    fn golden_path(name: &str) -> PathBuf {
+        PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/dashboard")).join(name)
+    }
+
+    /// Assert `actual` matches the checked-in golden file, or rewrite it in place when
+    /// `EXARP_UPDATE_GOLDEN` is set — the same escape hatch a deliberate layout change needs.
+    fn assert_golden(name: &str, actual: &str) {
+        let path = golden_path(name);
+        if std::env::var_os("EXARP_UPDATE_GOLDEN").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).expect("create testdata dir");
+            std::fs::write(&path, actual).expect("write golden file");
+            return;
+        }
+        let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+            panic!("missing golden file {path:?} — run with EXARP_UPDATE_GOLDEN=1 to create it")
+        });
+        assert_eq!(actual, expected, "render_ui drifted from golden {path:?}");
+    }
+
+    #[test]
+    fn renders_quiet_dashboard_with_no_alerts() {
+        let mut cthonian = Source::new("cthonian", "/home");
+        cthonian.entropy = 4.1;
+        cthonian.files = 128;
+        cthonian.history = vec![4.0, 4.05, 4.1];
+        cthonian.scanned_once = true;
+
+        let config = AlertConfig::default();
+        let rules = rules::default_rules(&config);
+        let mut app = App {
+            sources: vec![cthonian],
+            selected: 0,
+            alerts: Vec::new(),
+            tick: 0,
+            rules,
+            restic_runner: None,
+            restic_snapshots: None,
+            restic_expected_interval_hours: 24.0,
+            inspector: None,
+        };
+        app.evaluate_rules();
+
+        assert_golden("quiet.golden", &render_to_string(&app));
+    }
+
+    #[test]
+    fn renders_selection_and_alerts_after_a_tick_sequence() {
+        let mut app = harness_app();
+        // Script: select the second source, then let a few spinner ticks pass.
+        app.selected = 1;
+        for _ in 0..3 {
+            app.tick_spinner();
+        }
+        assert_golden("selected_with_alerts.golden", &render_to_string(&app));
+    }
+}
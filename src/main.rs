@@ -1,17 +1,31 @@
 #[cfg(feature = "tui")]
+mod config;
+#[cfg(feature = "tui")]
 mod dashboard;
+#[cfg(feature = "tui")]
+mod preview;
+#[cfg(feature = "tui")]
+mod restic;
+#[cfg(feature = "tui")]
+mod rules;
+mod webhook;
+
+use webhook::WebhookFormat;
 
 use anyhow::Result;
 use chrono::Utc;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
 use walkdir::WalkDir;
 
 /// Exarp — Behavioral backup intelligence.
@@ -39,8 +53,16 @@ enum Commands {
         /// Sample size per file in bytes
         #[arg(long, default_value = "65536")]
         sample_size: usize,
+        /// Max number of 4096-byte blocks to profile per file for partial-encryption detection
+        #[arg(long, default_value = "256")]
+        max_blocks: usize,
+        /// Output format for the stdout summary (the baseline file is always pretty JSON)
+        #[arg(long, value_enum, default_value = "human")]
+        format: ScanFormat,
     },
-    /// Compare current state against a saved baseline
+    /// Compare current state against a saved baseline.
+    ///
+    /// Exit code contract for CI: 0 = OK, 1 = HIGH severity, 2 = CRITICAL severity.
     Check {
         /// Path to scan
         path: PathBuf,
@@ -50,6 +72,21 @@ enum Commands {
         /// Entropy spike threshold (bits/byte)
         #[arg(long, default_value = "1.5")]
         threshold: f64,
+        /// Chi-square acceptance band (255 d.o.f.) for "looks truly random" as LOW-HIGH
+        #[arg(long, default_value = "150-350")]
+        chi_square_band: ChiSquareBand,
+        /// Bypass the mtime+size cache and re-read every file (also enables timestomping detection)
+        #[arg(long)]
+        no_cache: bool,
+        /// Webhook URL to notify on HIGH/CRITICAL results
+        #[arg(long)]
+        webhook: Option<String>,
+        /// Override the webhook payload format (auto-detected from the URL otherwise)
+        #[arg(long)]
+        webhook_format: Option<WebhookFormat>,
+        /// Output format for the stdout result
+        #[arg(long, value_enum, default_value = "human")]
+        format: CheckFormat,
     },
     /// Interactive TUI dashboard with live graphs (requires 'tui' feature)
     Dashboard,
@@ -57,12 +94,73 @@ enum Commands {
     Watch {
         /// Path to watch
         path: PathBuf,
-        /// Check interval in seconds
+        /// Maximum coalescing window in seconds; a window with no fs events ends without a rescan
         #[arg(short, long, default_value = "300")]
         interval: u64,
-        /// Webhook URL for alerts
+        /// Webhook URL to notify on HIGH/CRITICAL results
         #[arg(long)]
         webhook: Option<String>,
+        /// Override the webhook payload format (auto-detected from the URL otherwise)
+        #[arg(long)]
+        webhook_format: Option<WebhookFormat>,
+        /// Filesystem events/sec across the tree above which a burst is flagged as rapid_mutation
+        #[arg(long, default_value = "50.0")]
+        event_rate_threshold: f64,
+    },
+    /// Restic repository commands (requires 'tui' feature)
+    #[command(subcommand)]
+    Restic(ResticCommand),
+}
+
+/// Subcommands operating on the restic repositories configured in `~/.exarp/config.toml`.
+#[derive(Subcommand)]
+enum ResticCommand {
+    /// Preview (or apply) the standing retention policy from `[forget]` in config.toml
+    Forget {
+        /// Actually forget + prune the snapshots the policy drops (default: dry-run preview only)
+        #[arg(long)]
+        apply: bool,
+        /// Emit machine-readable JSON instead of the human summary
+        #[arg(long)]
+        json: bool,
+    },
+    /// Statistical drift analysis across every consecutive snapshot pair
+    Drift {
+        /// Emit machine-readable JSON instead of the human summary
+        #[arg(long)]
+        json: bool,
+    },
+    /// Aggregated status across every configured repository (`[restic]` plus `[[repositories]]`)
+    Status {
+        /// Emit machine-readable JSON instead of the human summary
+        #[arg(long)]
+        json: bool,
+        /// Print Prometheus exposition-format text for the `[restic]` repo to stdout and exit
+        /// (for a node_exporter textfile-collector cron job). Takes precedence over --json.
+        #[arg(long)]
+        metrics: bool,
+        /// Serve Prometheus metrics for the `[restic]` repo over HTTP at ADDR (e.g. 127.0.0.1:9100)
+        /// instead of printing a one-shot status. Takes precedence over --metrics and --json.
+        #[arg(long, value_name = "ADDR")]
+        serve: Option<String>,
+    },
+    /// Resident monitor: polls status/check/drift on the intervals configured in `[watch]`
+    Watch {
+        /// Emit machine-readable JSON events instead of human-readable lines
+        #[arg(long)]
+        json: bool,
+    },
+    /// List snapshots in the `[restic]` repository
+    Snapshots {
+        /// Emit machine-readable JSON instead of the human table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Run `restic check` against the `[restic]` repository
+    Check {
+        /// Emit machine-readable JSON instead of the human summary
+        #[arg(long)]
+        json: bool,
     },
 }
 
@@ -72,6 +170,12 @@ struct FileScan {
     entropy: f64,
     size: u64,
     extension: String,
+    /// Highest per-block entropy seen while walking the file in `BLOCK_SIZE` chunks
+    block_max_entropy: f64,
+    /// Fraction of sampled blocks above `HIGH_ENTROPY_BLOCK_CUTOFF`
+    high_entropy_block_ratio: f64,
+    /// Chi-square statistic (256 bins, 255 d.o.f.) over the sampled byte histogram
+    chi_square: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -84,6 +188,8 @@ struct ScanResult {
     very_high_count: usize,     // > 7.9
     by_extension: HashMap<String, ExtStats>,
     suspicious: Vec<FileScan>,
+    /// Per-file cache keyed by path, doubling the baseline as an incremental-scan cache
+    file_cache: HashMap<String, CacheEntry>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -92,33 +198,78 @@ struct ExtStats {
     avg_entropy: f64,
 }
 
+/// Cached per-file stats keyed on `(mtime, size)`; a match lets a rescan skip the disk read
+/// entirely, and the `fingerprint` lets a forced re-read (`--no-cache`) catch content that
+/// changed while mtime/size were restored to their original values ("timestomping").
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct CacheEntry {
+    mtime: i64,
+    size: u64,
+    extension: String,
+    entropy: f64,
+    chi_square: f64,
+    block_max_entropy: f64,
+    high_entropy_block_ratio: f64,
+    fingerprint: u64,
+}
+
+impl CacheEntry {
+    fn to_file_scan(&self, path: &str) -> FileScan {
+        FileScan {
+            path: path.to_string(),
+            entropy: self.entropy,
+            size: self.size,
+            extension: self.extension.clone(),
+            block_max_entropy: self.block_max_entropy,
+            high_entropy_block_ratio: self.high_entropy_block_ratio,
+            chi_square: self.chi_square,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
-struct Alert {
-    severity: String,
-    signal: String,
-    message: String,
+pub(crate) struct Alert {
+    pub(crate) severity: String,
+    pub(crate) signal: String,
+    pub(crate) message: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct CheckResult {
-    timestamp: String,
-    baseline_time: String,
-    severity: String,
-    entropy_delta: f64,
-    alerts: Vec<Alert>,
+pub(crate) struct CheckResult {
+    pub(crate) timestamp: String,
+    pub(crate) baseline_time: String,
+    pub(crate) severity: String,
+    pub(crate) entropy_delta: f64,
+    pub(crate) alerts: Vec<Alert>,
 }
 
-/// Calculate Shannon entropy of a byte buffer (bits per byte)
-fn entropy(data: &[u8]) -> f64 {
-    if data.is_empty() {
-        return 0.0;
-    }
+/// Size of each block sampled by `block_entropy_profile`
+const BLOCK_SIZE: usize = 4096;
+/// Per-block entropy above this is considered "high" when computing `high_entropy_block_ratio`
+const HIGH_ENTROPY_BLOCK_CUTOFF: f64 = 7.9;
+/// Default cap on the number of blocks profiled per file (4096 * 256 = 1 MiB)
+const DEFAULT_MAX_BLOCKS: usize = 256;
+/// `high_entropy_block_ratio` above this marks a file as partially/header-preserving encrypted
+const PARTIAL_ENCRYPTION_BLOCK_RATIO: f64 = 0.3;
+/// Head/whole-file entropy at or below this "looks benign" to the coarse scalar check
+const BENIGN_HEAD_ENTROPY: f64 = 6.0;
 
+/// Build a 256-bin byte-frequency histogram over a buffer
+fn byte_histogram(data: &[u8]) -> [u64; 256] {
     let mut counts = [0u64; 256];
     for &byte in data {
         counts[byte as usize] += 1;
     }
+    counts
+}
+
+/// Calculate Shannon entropy of a byte buffer (bits per byte)
+fn entropy(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
 
+    let counts = byte_histogram(data);
     let len = data.len() as f64;
     let mut entropy = 0.0;
 
@@ -132,22 +283,103 @@ fn entropy(data: &[u8]) -> f64 {
     entropy
 }
 
-/// Calculate entropy of a file, sampling first N bytes
-fn file_entropy(path: &Path, sample_size: usize) -> Result<f64> {
+/// Chi-square goodness-of-fit statistic against a uniform byte distribution (255 d.o.f.).
+///
+/// Truly random/encrypted data lands close to 255; compressed data keeps enough structural
+/// bias to usually fall well outside the ~150–350 acceptance band, which is what lets
+/// `compare_scans` tell the two apart instead of relying on a file extension allowlist.
+fn chi_square(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+
+    let counts = byte_histogram(data);
+    let expected = data.len() as f64 / 256.0;
+
+    counts
+        .iter()
+        .map(|&count| {
+            let diff = count as f64 - expected;
+            diff * diff / expected
+        })
+        .sum()
+}
+
+/// Calculate entropy, chi-square, and a content fingerprint of a file, sampling first N bytes
+fn file_stats(path: &Path, sample_size: usize) -> Result<(f64, f64, u64)> {
     let mut file = fs::File::open(path)?;
     let mut buffer = vec![0u8; sample_size];
     let bytes_read = file.read(&mut buffer)?;
     buffer.truncate(bytes_read);
 
     if bytes_read < 64 {
-        return Ok(0.0);
+        return Ok((0.0, 0.0, 0));
     }
 
-    Ok(entropy(&buffer))
+    Ok((entropy(&buffer), chi_square(&buffer), md5_simple(&buffer)))
 }
 
-/// Collect all scannable file paths from a directory
-fn collect_files(path: &Path) -> Vec<PathBuf> {
+/// Walk a file in fixed-size blocks and return `(block_max_entropy, high_entropy_block_ratio)`.
+///
+/// Ransomware that preserves a readable header or only encrypts part of a file keeps the
+/// whole-file/head-sample entropy low, so this looks at every block independently: files
+/// smaller than one block fall back to a single block covering the whole read, and a short
+/// trailing block (<64 bytes) is dropped rather than counted, matching `file_stats`'s
+/// 64-byte minimum.
+fn block_entropy_profile(path: &Path, max_blocks: usize) -> Result<(f64, f64)> {
+    let mut file = fs::File::open(path)?;
+    let mut buffer = vec![0u8; BLOCK_SIZE];
+    let mut counts = [0u64; 256];
+    let mut block_max_entropy = 0.0_f64;
+    let mut high_blocks = 0usize;
+    let mut total_blocks = 0usize;
+
+    while total_blocks < max_blocks {
+        let bytes_read = file.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        if bytes_read < 64 {
+            break;
+        }
+
+        for c in counts.iter_mut() {
+            *c = 0;
+        }
+        for &byte in &buffer[..bytes_read] {
+            counts[byte as usize] += 1;
+        }
+
+        let len = bytes_read as f64;
+        let mut block_entropy = 0.0;
+        for &count in &counts {
+            if count > 0 {
+                let p = count as f64 / len;
+                block_entropy -= p * p.log2();
+            }
+        }
+
+        if block_entropy > block_max_entropy {
+            block_max_entropy = block_entropy;
+        }
+        if block_entropy > HIGH_ENTROPY_BLOCK_CUTOFF {
+            high_blocks += 1;
+        }
+        total_blocks += 1;
+    }
+
+    let high_entropy_block_ratio = if total_blocks > 0 {
+        high_blocks as f64 / total_blocks as f64
+    } else {
+        0.0
+    };
+
+    Ok((block_max_entropy, high_entropy_block_ratio))
+}
+
+/// Collect scannable files as `(path, size, mtime)`, reusing the single `DirEntry::metadata()`
+/// stat instead of re-statting each path again later in `scan_directory`
+fn collect_files(path: &Path) -> Vec<(PathBuf, u64, i64)> {
     let skip_dirs: std::collections::HashSet<&str> = [
         ".git", "node_modules", "__pycache__", ".cache", ".venv", "venv",
     ]
@@ -165,13 +397,93 @@ fn collect_files(path: &Path) -> Vec<PathBuf> {
         })
         .filter_map(|e| e.ok())
         .filter(|e| e.file_type().is_file())
-        .filter(|e| e.metadata().map(|m| m.len() >= 64).unwrap_or(false))
-        .map(|e| e.into_path())
+        .filter_map(|e| {
+            let meta = e.metadata().ok()?;
+            if meta.len() < 64 {
+                return None;
+            }
+            let mtime = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            Some((e.into_path(), meta.len(), mtime))
+        })
         .collect()
 }
 
-/// Scan a directory and return results
-fn scan_directory(path: &Path, sample_size: usize) -> Result<ScanResult> {
+/// Read and fully analyze a single file, producing both its `FileScan` and the `CacheEntry`
+/// to store for it. Shared by the full-tree walk in `scan_directory` and the `Watch` event
+/// loop, which only needs to re-run this for the handful of files an fs event touched.
+fn analyze_file(
+    fpath: &Path,
+    path_str: &str,
+    size: u64,
+    mtime: i64,
+    extension: String,
+    sample_size: usize,
+    max_blocks: usize,
+) -> Option<(FileScan, CacheEntry)> {
+    let (entropy, chi_square, fingerprint) = file_stats(fpath, sample_size).ok()?;
+    let (block_max_entropy, high_entropy_block_ratio) =
+        block_entropy_profile(fpath, max_blocks).ok()?;
+
+    let entry = CacheEntry {
+        mtime,
+        size,
+        extension: extension.clone(),
+        entropy,
+        chi_square,
+        block_max_entropy,
+        high_entropy_block_ratio,
+        fingerprint,
+    };
+    let scan = FileScan {
+        path: path_str.to_string(),
+        entropy,
+        size,
+        extension,
+        block_max_entropy,
+        high_entropy_block_ratio,
+        chi_square,
+    };
+    Some((scan, entry))
+}
+
+/// Stat and analyze a single path, for rescanning a file touched by a filesystem event
+fn analyze_path(fpath: &Path, sample_size: usize, max_blocks: usize) -> Option<(FileScan, CacheEntry)> {
+    let meta = fs::metadata(fpath).ok()?;
+    if !meta.is_file() || meta.len() < 64 {
+        return None;
+    }
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let path_str = fpath.to_string_lossy().to_string();
+    let ext = fpath
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| format!(".{}", e.to_lowercase()))
+        .unwrap_or_default();
+
+    analyze_file(fpath, &path_str, meta.len(), mtime, ext, sample_size, max_blocks)
+}
+
+/// Scan a directory and return results. When `prev_cache` is given, files whose mtime and size
+/// match a cached entry reuse the stored stats and skip the disk read entirely; pass
+/// `force_full = true` to ignore the cache (e.g. to verify a file via its fingerprint even
+/// when mtime/size look unchanged).
+fn scan_directory(
+    path: &Path,
+    sample_size: usize,
+    max_blocks: usize,
+    prev_cache: Option<&HashMap<String, CacheEntry>>,
+    force_full: bool,
+) -> Result<ScanResult> {
     let files = collect_files(path);
     let total = files.len();
 
@@ -183,31 +495,63 @@ fn scan_directory(path: &Path, sample_size: usize) -> Result<ScanResult> {
             .progress_chars("█▓░"),
     );
 
-    // Parallel entropy calculation
-    let scans: Vec<FileScan> = files
+    // Parallel entropy calculation, with a cache fast-path keyed on (mtime, size)
+    let scanned: Vec<(FileScan, CacheEntry)> = files
         .par_iter()
-        .filter_map(|fpath| {
+        .filter_map(|(fpath, size, mtime)| {
             pb.inc(1);
-            let ent = file_entropy(fpath, sample_size).ok()?;
-            let meta = fs::metadata(fpath).ok()?;
+            let path_str = fpath.to_string_lossy().to_string();
             let ext = fpath
                 .extension()
                 .and_then(|e| e.to_str())
                 .map(|e| format!(".{}", e.to_lowercase()))
                 .unwrap_or_default();
 
-            Some(FileScan {
-                path: fpath.to_string_lossy().to_string(),
-                entropy: ent,
-                size: meta.len(),
-                extension: ext,
-            })
+            if !force_full {
+                if let Some(cached) = prev_cache.and_then(|c| c.get(&path_str)) {
+                    if cached.mtime == *mtime && cached.size == *size {
+                        return Some((cached.to_file_scan(&path_str), cached.clone()));
+                    }
+                }
+            }
+
+            analyze_file(fpath, &path_str, *size, *mtime, ext, sample_size, max_blocks)
         })
         .collect();
 
     pb.finish_and_clear();
 
+    let mut scans: Vec<FileScan> = Vec::with_capacity(scanned.len());
+    let mut file_cache: HashMap<String, CacheEntry> = HashMap::with_capacity(scanned.len());
+    for (scan, entry) in scanned {
+        file_cache.insert(scan.path.clone(), entry);
+        scans.push(scan);
+    }
+
     // Aggregate stats
+    let total_files = scans.len();
+    let (avg_entropy, high_entropy_count, very_high_count, by_extension, suspicious) =
+        aggregate_scans(&scans);
+
+    Ok(ScanResult {
+        path: path.to_string_lossy().to_string(),
+        timestamp: Utc::now().to_rfc3339(),
+        total_files,
+        avg_entropy,
+        high_entropy_count,
+        very_high_count,
+        by_extension,
+        suspicious,
+        file_cache,
+    })
+}
+
+/// Fold a flat list of `FileScan`s into the aggregate stats stored on `ScanResult`. Shared by
+/// `scan_directory` (full tree) and the `Watch` event loop (incremental rebuild after a batch
+/// of touched files is re-analyzed), so the aggregates stay identical either way.
+fn aggregate_scans(
+    scans: &[FileScan],
+) -> (f64, usize, usize, HashMap<String, ExtStats>, Vec<FileScan>) {
     let total_files = scans.len();
     let avg_entropy = if total_files > 0 {
         scans.iter().map(|s| s.entropy).sum::<f64>() / total_files as f64
@@ -220,7 +564,7 @@ fn scan_directory(path: &Path, sample_size: usize) -> Result<ScanResult> {
 
     // By extension
     let mut ext_totals: HashMap<String, (usize, f64)> = HashMap::new();
-    for scan in &scans {
+    for scan in scans {
         let entry = ext_totals.entry(scan.extension.clone()).or_insert((0, 0.0));
         entry.0 += 1;
         entry.1 += scan.entropy;
@@ -239,7 +583,8 @@ fn scan_directory(path: &Path, sample_size: usize) -> Result<ScanResult> {
         })
         .collect();
 
-    // Suspicious files (>7.9 entropy, excluding known compressed types)
+    // Suspicious files (>7.9 entropy, or a large ratio of high-entropy interior blocks),
+    // excluding known compressed types
     let compressed_exts: std::collections::HashSet<&str> = [
         ".zip", ".gz", ".bz2", ".xz", ".7z", ".rar", ".zst",
         ".mp4", ".mkv", ".avi", ".mov", ".webm",
@@ -252,24 +597,66 @@ fn scan_directory(path: &Path, sample_size: usize) -> Result<ScanResult> {
 
     let suspicious: Vec<FileScan> = scans
         .iter()
-        .filter(|s| s.entropy > 7.9 && !compressed_exts.contains(s.extension.as_str()))
+        .filter(|s| {
+            !compressed_exts.contains(s.extension.as_str())
+                && (s.entropy > 7.9 || s.high_entropy_block_ratio > PARTIAL_ENCRYPTION_BLOCK_RATIO)
+        })
         .cloned()
         .collect();
 
-    Ok(ScanResult {
-        path: path.to_string_lossy().to_string(),
-        timestamp: Utc::now().to_rfc3339(),
-        total_files,
+    (
         avg_entropy,
         high_entropy_count,
         very_high_count,
         by_extension,
         suspicious,
-    })
+    )
+}
+
+/// Acceptance band for the chi-square statistic (255 d.o.f.) used to decide whether a
+/// high-entropy population "looks encrypted" rather than merely compressed
+#[derive(Debug, Clone, Copy)]
+struct ChiSquareBand {
+    low: f64,
+    high: f64,
+}
+
+impl ChiSquareBand {
+    fn contains(&self, value: f64) -> bool {
+        value >= self.low && value <= self.high
+    }
+}
+
+impl Default for ChiSquareBand {
+    fn default() -> Self {
+        ChiSquareBand {
+            low: 150.0,
+            high: 350.0,
+        }
+    }
+}
+
+impl std::str::FromStr for ChiSquareBand {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (low, high) = s
+            .split_once('-')
+            .ok_or_else(|| anyhow::anyhow!("expected LOW-HIGH, e.g. 150-350"))?;
+        Ok(ChiSquareBand {
+            low: low.trim().parse()?,
+            high: high.trim().parse()?,
+        })
+    }
 }
 
 /// Compare two scans
-fn compare_scans(baseline: &ScanResult, current: &ScanResult, threshold: f64) -> CheckResult {
+fn compare_scans(
+    baseline: &ScanResult,
+    current: &ScanResult,
+    threshold: f64,
+    chi_square_band: ChiSquareBand,
+) -> CheckResult {
     let mut alerts = Vec::new();
     let ent_delta = current.avg_entropy - baseline.avg_entropy;
 
@@ -285,7 +672,9 @@ fn compare_scans(baseline: &ScanResult, current: &ScanResult, threshold: f64) ->
         });
     }
 
-    // Mass encryption detection
+    // Mass encryption detection. High entropy alone also fires on compressed-data floods, so
+    // CRITICAL requires that at least half of the current suspicious files also have a
+    // near-ideal chi-square; a flood of high-entropy files with structural bias is downgraded to HIGH.
     let vh_delta = current.very_high_count as i64 - baseline.very_high_count as i64;
     let vh_pct = if baseline.total_files > 0 {
         (vh_delta as f64 / baseline.total_files as f64) * 100.0
@@ -294,12 +683,25 @@ fn compare_scans(baseline: &ScanResult, current: &ScanResult, threshold: f64) ->
     };
 
     if vh_pct > 20.0 {
+        let random_like = if current.suspicious.is_empty() {
+            0.0
+        } else {
+            current
+                .suspicious
+                .iter()
+                .filter(|s| chi_square_band.contains(s.chi_square))
+                .count() as f64
+                / current.suspicious.len() as f64
+        };
+
+        let severity = if random_like >= 0.5 { "CRITICAL" } else { "HIGH" };
+
         alerts.push(Alert {
-            severity: "CRITICAL".into(),
+            severity: severity.into(),
             signal: "mass_encryption".into(),
             message: format!(
-                "{} new files with entropy >7.9 bits/byte ({:.0}% of files)",
-                vh_delta, vh_pct
+                "{} new files with entropy >7.9 bits/byte ({:.0}% of files, {:.0}% chi-square-like random)",
+                vh_delta, vh_pct, random_like * 100.0
             ),
         });
     }
@@ -329,6 +731,58 @@ fn compare_scans(baseline: &ScanResult, current: &ScanResult, threshold: f64) ->
         }
     }
 
+    // Partial / header-preserving encryption: the whole-file entropy looks benign but a large
+    // share of interior blocks are already near-random, which a head-sample or mean check misses.
+    let partial_encryption: Vec<&FileScan> = current
+        .suspicious
+        .iter()
+        .filter(|s| {
+            s.entropy <= BENIGN_HEAD_ENTROPY
+                && s.high_entropy_block_ratio > PARTIAL_ENCRYPTION_BLOCK_RATIO
+        })
+        .collect();
+
+    if !partial_encryption.is_empty() {
+        alerts.push(Alert {
+            severity: "CRITICAL".into(),
+            signal: "partial_encryption".into(),
+            message: format!(
+                "{} file(s) have a benign head but {:.0}%+ of interior blocks read as high-entropy (e.g. {})",
+                partial_encryption.len(),
+                PARTIAL_ENCRYPTION_BLOCK_RATIO * 100.0,
+                partial_encryption[0].path
+            ),
+        });
+    }
+
+    // Timestomping: content changed (different fingerprint) while mtime AND size were restored
+    // to their cached values. This only shows up when `current` was rescanned with the cache
+    // bypassed (e.g. `exarp check --no-cache`), since a cache hit just reuses the old fingerprint.
+    let timestomped: Vec<&str> = current
+        .file_cache
+        .iter()
+        .filter_map(|(path, cur)| {
+            let base = baseline.file_cache.get(path)?;
+            if base.mtime == cur.mtime && base.size == cur.size && base.fingerprint != cur.fingerprint {
+                Some(path.as_str())
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    if !timestomped.is_empty() {
+        alerts.push(Alert {
+            severity: "HIGH".into(),
+            signal: "timestomping".into(),
+            message: format!(
+                "{} file(s) changed content while mtime/size matched the baseline (e.g. {}) — possible timestomping",
+                timestomped.len(),
+                timestomped[0]
+            ),
+        });
+    }
+
     // New suspicious files not in known-compressed categories
     if !current.suspicious.is_empty() && current.suspicious.len() > baseline.suspicious.len() + 5 {
         alerts.push(Alert {
@@ -360,6 +814,92 @@ fn compare_scans(baseline: &ScanResult, current: &ScanResult, threshold: f64) ->
     }
 }
 
+/// Output format for `Scan`'s stdout summary (the baseline file on disk is always pretty JSON)
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ScanFormat {
+    Human,
+    Json,
+    Compact,
+    Csv,
+}
+
+/// Output format for `Check`'s stdout result
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CheckFormat {
+    Human,
+    Json,
+    Compact,
+    Csv,
+    /// SARIF 2.1.0, so alerts surface directly in code-scanning dashboards
+    Sarif,
+}
+
+/// Quote a CSV field if it contains a comma, quote, or newline
+fn csv_escape(s: &str) -> String {
+    if s.contains(',') || s.contains('"') || s.contains('\n') {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn print_scan_csv(result: &ScanResult) {
+    println!("path,entropy,chi_square,block_max_entropy,high_entropy_block_ratio,size,extension");
+    for f in &result.suspicious {
+        println!(
+            "{},{:.4},{:.2},{:.4},{:.4},{},{}",
+            csv_escape(&f.path),
+            f.entropy,
+            f.chi_square,
+            f.block_max_entropy,
+            f.high_entropy_block_ratio,
+            f.size,
+            csv_escape(&f.extension)
+        );
+    }
+}
+
+fn print_check_csv(result: &CheckResult) {
+    println!("severity,signal,message");
+    for a in &result.alerts {
+        println!("{},{},{}", a.severity, a.signal, csv_escape(&a.message));
+    }
+}
+
+/// Render a `CheckResult` as a minimal SARIF 2.1.0 log, one result per alert
+fn check_to_sarif(result: &CheckResult) -> serde_json::Value {
+    let results: Vec<serde_json::Value> = result
+        .alerts
+        .iter()
+        .map(|a| {
+            serde_json::json!({
+                "ruleId": a.signal,
+                "level": match a.severity.as_str() {
+                    "CRITICAL" => "error",
+                    "HIGH" => "warning",
+                    _ => "note",
+                },
+                "message": { "text": a.message },
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "$schema": "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/master/Schemata/sarif-schema-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "exarp",
+                    "informationUri": "https://github.com/flint-dominic/exarp",
+                    "version": env!("CARGO_PKG_VERSION"),
+                }
+            },
+            "results": results,
+        }],
+    })
+}
+
 fn print_scan(result: &ScanResult) {
     println!("{}", "🛡️  Exarp Entropy Scanner".cyan().bold());
     println!("{}", "═".repeat(50).cyan());
@@ -387,8 +927,11 @@ fn print_scan(result: &ScanResult) {
         );
         for f in result.suspicious.iter().take(10) {
             println!(
-                "    {:.4} b/B  {:>10} bytes  {}",
+                "    {:.4} b/B  χ²={:.0}  (block max {:.2}, {:.0}% high blocks)  {:>10} bytes  {}",
                 f.entropy,
+                f.chi_square,
+                f.block_max_entropy,
+                f.high_entropy_block_ratio * 100.0,
                 f.size,
                 f.path.dimmed()
             );
@@ -494,9 +1037,17 @@ fn main() -> Result<()> {
             path,
             output,
             sample_size,
+            max_blocks,
+            format,
         } => {
-            let result = scan_directory(&path, sample_size)?;
-            print_scan(&result);
+            let result = scan_directory(&path, sample_size, max_blocks, None, false)?;
+
+            match format {
+                ScanFormat::Human => print_scan(&result),
+                ScanFormat::Json => println!("{}", serde_json::to_string_pretty(&result)?),
+                ScanFormat::Compact => println!("{}", serde_json::to_string(&result)?),
+                ScanFormat::Csv => print_scan_csv(&result),
+            }
 
             let out_path = output.unwrap_or_else(|| {
                 let hash = format!("{:x}", md5_simple(path.to_string_lossy().as_bytes()));
@@ -505,19 +1056,47 @@ fn main() -> Result<()> {
 
             let json = serde_json::to_string_pretty(&result)?;
             fs::write(&out_path, &json)?;
-            println!("\n  Baseline saved: {}", out_path.display().to_string().green());
+            if matches!(format, ScanFormat::Human) {
+                println!("\n  Baseline saved: {}", out_path.display().to_string().green());
+            }
         }
 
         Commands::Check {
             path,
             baseline,
             threshold,
+            chi_square_band,
+            no_cache,
+            webhook,
+            webhook_format,
+            format,
         } => {
             let baseline_json = fs::read_to_string(&baseline)?;
             let baseline_data: ScanResult = serde_json::from_str(&baseline_json)?;
-            let current = scan_directory(&path, 65536)?;
-            let result = compare_scans(&baseline_data, &current, threshold);
-            print_check(&result);
+            let current = scan_directory(
+                &path,
+                65536,
+                DEFAULT_MAX_BLOCKS,
+                Some(&baseline_data.file_cache),
+                no_cache,
+            )?;
+            let result = compare_scans(&baseline_data, &current, threshold, chi_square_band);
+
+            match format {
+                CheckFormat::Human => print_check(&result),
+                CheckFormat::Json => println!("{}", serde_json::to_string_pretty(&result)?),
+                CheckFormat::Compact => println!("{}", serde_json::to_string(&result)?),
+                CheckFormat::Csv => print_check_csv(&result),
+                CheckFormat::Sarif => {
+                    println!("{}", serde_json::to_string_pretty(&check_to_sarif(&result))?)
+                }
+            }
+
+            if let Some(url) = &webhook {
+                if let Err(err) = webhook::notify(url, webhook_format, &result) {
+                    eprintln!("  {} {}", "⚠".yellow(), err);
+                }
+            }
 
             if result.severity == "CRITICAL" {
                 std::process::exit(2);
@@ -529,33 +1108,56 @@ fn main() -> Result<()> {
         Commands::Watch {
             path,
             interval,
-            webhook: _,
+            webhook,
+            webhook_format,
+            event_rate_threshold,
         } => {
-            println!("{}", "🛡️  Exarp Watch Mode".cyan().bold());
-            println!("  Monitoring: {}", path.display());
-            println!("  Interval: {}s", interval);
-            println!("  Press Ctrl+C to stop\n");
-
-            // Initial baseline
-            println!("  Establishing baseline...");
-            let mut baseline = scan_directory(&path, 65536)?;
-            print_scan(&baseline);
-
-            loop {
-                std::thread::sleep(std::time::Duration::from_secs(interval));
-                println!("\n  {} Rescanning...", "🔄".cyan());
-                let current = scan_directory(&path, 65536)?;
-                let result = compare_scans(&baseline, &current, 1.5);
-                print_check(&result);
-
-                if result.severity == "OK" {
-                    // Update baseline on clean scan
-                    baseline = current;
-                } else {
-                    println!(
-                        "\n  {} Baseline preserved (last known clean state)",
-                        "📌".yellow()
-                    );
+            run_watch(&path, interval, event_rate_threshold, webhook, webhook_format)?;
+        }
+
+        Commands::Restic(action) => {
+            #[cfg(not(feature = "tui"))]
+            {
+                let _ = action;
+                anyhow::bail!("Restic commands require the 'tui' feature. Rebuild with: cargo build --features tui");
+            }
+            #[cfg(feature = "tui")]
+            {
+                let cfg = config::ExarpConfig::load()?;
+                match action {
+                    ResticCommand::Forget { apply, json } => {
+                        let runner = restic::ResticRunner::from_config(&cfg);
+                        let policy = restic::ForgetPolicy::from_config(&cfg);
+                        restic::cmd_forget(&runner, &policy, apply, json)?;
+                    }
+                    ResticCommand::Drift { json } => {
+                        let runner = restic::ResticRunner::from_config(&cfg);
+                        restic::cmd_drift(&runner, json, &cfg)?;
+                    }
+                    ResticCommand::Status { json, metrics, serve } => {
+                        if let Some(addr) = serve {
+                            let runner = restic::ResticRunner::from_config(&cfg);
+                            restic::serve_metrics(&runner, &cfg, &addr)?;
+                        } else if metrics {
+                            let runner = restic::ResticRunner::from_config(&cfg);
+                            restic::cmd_metrics(&runner, &cfg)?;
+                        } else {
+                            let multi = restic::MultiRunner::from_config(&cfg)?;
+                            restic::cmd_status_multi(&multi, json)?;
+                        }
+                    }
+                    ResticCommand::Watch { json } => {
+                        let runner = restic::ResticRunner::from_config(&cfg);
+                        restic::cmd_watch(&runner, &cfg, json)?;
+                    }
+                    ResticCommand::Snapshots { json } => {
+                        let runner = restic::ResticRunner::from_config(&cfg);
+                        restic::cmd_snapshots(&runner, json)?;
+                    }
+                    ResticCommand::Check { json } => {
+                        let runner = restic::ResticRunner::from_config(&cfg);
+                        restic::cmd_check(&runner, json)?;
+                    }
                 }
             }
         }
@@ -564,6 +1166,163 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// Debounce window: once events stop arriving for this long, flush the current batch early
+/// instead of waiting out the full `interval` heartbeat
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Event-driven replacement for the old sleep-then-rescan loop. Subscribes to filesystem
+/// events under `path`, debounces them into batches, and only re-analyzes the files a batch
+/// actually touched. `interval` still bounds how long a batch can coalesce before it's flushed;
+/// a window in which no events arrive simply starts the next one, with no rescan of the tree,
+/// so a change the watcher does not report is not picked up. A burst of events faster than
+/// `event_rate_threshold` per second is itself treated as the strongest signal — a velocity
+/// dimension a fixed-interval poller can never observe — and raises `rapid_mutation`.
+fn run_watch(
+    path: &Path,
+    interval: u64,
+    event_rate_threshold: f64,
+    webhook_url: Option<String>,
+    webhook_format: Option<WebhookFormat>,
+) -> Result<()> {
+    println!("{}", "🛡️  Exarp Watch Mode".cyan().bold());
+    println!("  Monitoring: {}", path.display());
+    println!("  Max coalescing window: {}s", interval);
+    println!("  Press Ctrl+C to stop\n");
+
+    println!("  Establishing baseline...");
+    let mut baseline = scan_directory(path, 65536, DEFAULT_MAX_BLOCKS, None, false)?;
+    print_scan(&baseline);
+
+    let mut tracked: HashMap<String, FileScan> = baseline
+        .file_cache
+        .iter()
+        .map(|(p, e)| (p.clone(), e.to_file_scan(p)))
+        .collect();
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher: RecommendedWatcher = notify::recommended_watcher(move |res| {
+        if let Ok(event) = res {
+            let _ = tx.send(event);
+        }
+    })?;
+    watcher.watch(path, RecursiveMode::Recursive)?;
+
+    let window = Duration::from_secs(interval.max(1));
+
+    loop {
+        let window_start = Instant::now();
+        let mut touched: HashSet<PathBuf> = HashSet::new();
+        let mut event_count: u64 = 0;
+        let mut last_event = window_start;
+
+        loop {
+            let elapsed = window_start.elapsed();
+            if elapsed >= window {
+                break;
+            }
+            let wait = WATCH_DEBOUNCE.min(window - elapsed);
+
+            match rx.recv_timeout(wait) {
+                Ok(event) => {
+                    event_count += 1;
+                    last_event = Instant::now();
+                    for p in event.paths {
+                        touched.insert(p);
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    if !touched.is_empty() && last_event.elapsed() >= WATCH_DEBOUNCE {
+                        break;
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    anyhow::bail!("filesystem watcher disconnected");
+                }
+            }
+        }
+
+        if touched.is_empty() {
+            continue;
+        }
+
+        let elapsed_secs = window_start.elapsed().as_secs_f64().max(0.001);
+        let event_rate = event_count as f64 / elapsed_secs;
+
+        println!(
+            "\n  {} {} touched file(s), {} event(s) ({:.1}/s)",
+            "🔄".cyan(),
+            touched.len(),
+            event_count,
+            event_rate
+        );
+
+        for p in &touched {
+            let path_str = p.to_string_lossy().to_string();
+            match analyze_path(p, 65536, DEFAULT_MAX_BLOCKS) {
+                Some((scan, entry)) => {
+                    tracked.insert(path_str.clone(), scan);
+                    baseline.file_cache.insert(path_str, entry);
+                }
+                None => {
+                    // File removed, unreadable, or below the minimum size — stop tracking it
+                    tracked.remove(&path_str);
+                    baseline.file_cache.remove(&path_str);
+                }
+            }
+        }
+
+        let all_scans: Vec<FileScan> = tracked.values().cloned().collect();
+        let (avg_entropy, high_entropy_count, very_high_count, by_extension, suspicious) =
+            aggregate_scans(&all_scans);
+
+        let current = ScanResult {
+            path: path.to_string_lossy().to_string(),
+            timestamp: Utc::now().to_rfc3339(),
+            total_files: all_scans.len(),
+            avg_entropy,
+            high_entropy_count,
+            very_high_count,
+            by_extension,
+            suspicious,
+            file_cache: baseline.file_cache.clone(),
+        };
+
+        let mut result = compare_scans(&baseline, &current, 1.5, ChiSquareBand::default());
+
+        if event_rate > event_rate_threshold {
+            result.alerts.push(Alert {
+                severity: "CRITICAL".into(),
+                signal: "rapid_mutation".into(),
+                message: format!(
+                    "{:.1} filesystem events/sec across {} file(s) in the last {:.0}s — burst faster than {:.1}/s threshold",
+                    event_rate,
+                    touched.len(),
+                    elapsed_secs,
+                    event_rate_threshold
+                ),
+            });
+            result.severity = "CRITICAL".into();
+        }
+
+        print_check(&result);
+
+        if let Some(url) = &webhook_url {
+            if let Err(err) = webhook::notify(url, webhook_format, &result) {
+                eprintln!("  {} {}", "⚠".yellow(), err);
+            }
+        }
+
+        if result.severity == "OK" {
+            baseline = current;
+        } else {
+            println!(
+                "\n  {} Baseline preserved (last known clean state)",
+                "📌".yellow()
+            );
+        }
+    }
+}
+
 /// Simple hash for baseline filenames (not crypto)
 fn md5_simple(data: &[u8]) -> u64 {
     let mut hash: u64 = 0xcbf29ce484222325;
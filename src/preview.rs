@@ -0,0 +1,104 @@
+//! File-inspection helpers for the dashboard's drill-down pane: classifying a file as
+//! text/binary, rendering a hex+ASCII dump, syntax-highlighting source text, and reading the
+//! permissions/owner/mtime shown in the preview footer. Kept separate from `dashboard` so the
+//! rendering module only has to turn these into `ratatui` widgets.
+
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Style as SynStyle, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+/// Hex-dump rows to render per file — enough to see whether a header is readable before the
+/// body goes full-entropy, without flooding the pane with a multi-megabyte file.
+const HEX_DUMP_MAX_ROWS: usize = 64;
+
+/// Heuristic for "worth syntax-highlighting": no NUL bytes and valid UTF-8 in the sampled
+/// region. Good enough to route obviously-binary/encrypted content to the hex dump instead of
+/// feeding garbage to syntect.
+pub fn looks_like_text(sample: &[u8]) -> bool {
+    !sample.contains(&0) && std::str::from_utf8(sample).is_ok()
+}
+
+/// Classic hex+ASCII dump: 16 bytes per row as hex, followed by the printable-ASCII gutter.
+pub fn hex_dump(bytes: &[u8]) -> Vec<String> {
+    bytes
+        .chunks(16)
+        .take(HEX_DUMP_MAX_ROWS)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let offset = i * 16;
+            let mut hex = chunk.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(" ");
+            if chunk.len() < 16 {
+                hex.push_str(&"   ".repeat(16 - chunk.len()));
+            }
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| if (0x20..0x7f).contains(&b) { b as char } else { '.' })
+                .collect();
+            format!("{:08x}  {}  |{}|", offset, hex, ascii)
+        })
+        .collect()
+}
+
+/// Syntax-highlight `contents` for `path`'s extension, returning each line as a list of
+/// `(style, text)` runs ready to map onto styled spans. Falls back to the plain-text syntax
+/// when the extension isn't recognized. Loads the default syntax/theme sets fresh each call —
+/// fine since the inspector only opens on an explicit keypress, not every frame.
+pub fn highlight_source(path: &Path, contents: &str) -> Vec<Vec<(SynStyle, String)>> {
+    let syntax_set = SyntaxSet::load_defaults_newlines();
+    let theme_set = ThemeSet::load_defaults();
+    let syntax = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(|ext| syntax_set.find_syntax_by_extension(ext))
+        .unwrap_or_else(|| syntax_set.find_syntax_plain_text());
+    let theme = &theme_set.themes["base16-ocean.dark"];
+    let mut highlighter = HighlightLines::new(syntax, theme);
+
+    LinesWithEndings::from(contents)
+        .map(|line| {
+            highlighter
+                .highlight_line(line, &syntax_set)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(style, text)| (style, text.to_string()))
+                .collect()
+        })
+        .collect()
+}
+
+/// Permissions, owner, and mtime for a file inspector's footer.
+pub struct FileMeta {
+    pub permissions: String,
+    pub owner_uid: u32,
+    pub mtime: DateTime<Utc>,
+}
+
+pub fn file_meta(path: &Path) -> std::io::Result<FileMeta> {
+    let meta = fs::metadata(path)?;
+    Ok(FileMeta {
+        permissions: format_mode(meta.mode()),
+        owner_uid: meta.uid(),
+        mtime: DateTime::<Utc>::from(meta.modified()?),
+    })
+}
+
+/// Render a `st_mode` as an `ls -l`-style `drwxr-xr-x` string.
+fn format_mode(mode: u32) -> String {
+    const BITS: [(u32, char); 9] = [
+        (0o400, 'r'), (0o200, 'w'), (0o100, 'x'),
+        (0o040, 'r'), (0o020, 'w'), (0o010, 'x'),
+        (0o004, 'r'), (0o002, 'w'), (0o001, 'x'),
+    ];
+    let mut s = String::with_capacity(10);
+    s.push(if mode & 0o170000 == 0o040000 { 'd' } else { '-' });
+    for (bit, ch) in BITS {
+        s.push(if mode & bit != 0 { ch } else { '-' });
+    }
+    s
+}
@@ -1,8 +1,11 @@
 use anyhow::{Context, Result, bail};
 use chrono::{DateTime, Utc};
 use colored::*;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::process::Command;
+use std::time::Instant;
 
 use crate::config::ExarpConfig;
 
@@ -47,7 +50,103 @@ pub struct DiffEntry {
     pub size: Option<u64>,
 }
 
+/// One group's worth of `restic forget --dry-run --json` output: which
+/// snapshots in that group `keep` and which `remove`, plus the grouping key restic evaluated
+/// the policy against.
 #[derive(Debug, Deserialize)]
+pub struct ForgetGroup {
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default)]
+    pub paths: Option<Vec<String>>,
+    #[serde(default)]
+    pub keep: Vec<Snapshot>,
+    #[serde(default)]
+    pub remove: Vec<Snapshot>,
+}
+
+/// A retention policy, mirroring restic's own `forget` flags one-for-one so `cmd_forget` can
+/// preview exactly what a real `restic forget` invocation would do before anyone passes
+/// `--apply`.
+#[derive(Debug, Clone, Default)]
+pub struct ForgetPolicy {
+    pub keep_last: Option<u32>,
+    pub keep_hourly: Option<u32>,
+    pub keep_daily: Option<u32>,
+    pub keep_weekly: Option<u32>,
+    pub keep_monthly: Option<u32>,
+    pub keep_yearly: Option<u32>,
+    /// Evaluate the policy separately per host
+    pub group_by_host: bool,
+    /// Evaluate the policy separately per snapshot path set
+    pub group_by_paths: bool,
+    /// Evaluate the policy separately per tag set
+    pub group_by_tags: bool,
+}
+
+impl ForgetPolicy {
+    /// Load the standing retention policy from `[forget]` in config.toml, defaulting grouping
+    /// to restic's own `host,paths` when the operator hasn't overridden it.
+    pub fn from_config(config: &ExarpConfig) -> Self {
+        let f = &config.forget;
+        Self {
+            keep_last: f.keep_last,
+            keep_hourly: f.keep_hourly,
+            keep_daily: f.keep_daily,
+            keep_weekly: f.keep_weekly,
+            keep_monthly: f.keep_monthly,
+            keep_yearly: f.keep_yearly,
+            group_by_host: f.group_by_host.unwrap_or(true),
+            group_by_paths: f.group_by_paths.unwrap_or(true),
+            group_by_tags: f.group_by_tags.unwrap_or(false),
+        }
+    }
+
+    /// Build `restic`'s `--group-by host,paths,tags` value from whichever grouping criteria
+    /// are enabled. Restic's own default is `host,paths`, which is also ours when nothing in
+    /// `[forget]` overrides it — and also when all three criteria are disabled, so grouping
+    /// can't be turned off entirely.
+    fn group_by_arg(&self) -> String {
+        let mut keys = Vec::new();
+        if self.group_by_host {
+            keys.push("host");
+        }
+        if self.group_by_paths {
+            keys.push("paths");
+        }
+        if self.group_by_tags {
+            keys.push("tags");
+        }
+        if keys.is_empty() {
+            "host,paths".to_string()
+        } else {
+            keys.join(",")
+        }
+    }
+
+    /// Translate the policy into `restic forget` arguments, omitting any `--keep-*` flag whose
+    /// count wasn't set so restic's own defaults (keep everything) apply to the rest.
+    fn args(&self) -> Vec<String> {
+        let mut args = vec!["forget".to_string(), "--group-by".to_string(), self.group_by_arg()];
+        let mut push = |flag: &str, value: Option<u32>| {
+            if let Some(n) = value {
+                args.push(flag.to_string());
+                args.push(n.to_string());
+            }
+        };
+        push("--keep-last", self.keep_last);
+        push("--keep-hourly", self.keep_hourly);
+        push("--keep-daily", self.keep_daily);
+        push("--keep-weekly", self.keep_weekly);
+        push("--keep-monthly", self.keep_monthly);
+        push("--keep-yearly", self.keep_yearly);
+        args
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct DiffSummary {
     #[serde(default)]
     pub files_new: u64,
@@ -209,6 +308,88 @@ impl ResticRunner {
             bail!("No output from restic diff");
         }
     }
+
+    /// Preview what `policy` would remove, without touching the repository. Always the first
+    /// step `cmd_forget` takes, so a caller can see kept-vs-removed per group before ever
+    /// passing `--apply`.
+    pub fn forget_dry_run(&self, policy: &ForgetPolicy) -> Result<Vec<ForgetGroup>> {
+        let mut args = policy.args();
+        args.push("--dry-run".to_string());
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+        let json = self.run(&args)?;
+        let groups: Vec<ForgetGroup> = serde_json::from_str(&json)
+            .context("Failed to parse restic forget --dry-run output")?;
+        Ok(groups)
+    }
+
+    /// Actually apply `policy`: forget the snapshots restic's own policy evaluation drops, then
+    /// prune the now-unreferenced data from the repository. Only ever called once a `--apply`
+    /// flag confirms the dry-run preview looked right.
+    pub fn forget_apply(&self, policy: &ForgetPolicy) -> Result<String> {
+        let mut args = policy.args();
+        args.push("--prune".to_string());
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        self.run(&args)
+    }
+}
+
+// ── Multi-repository fan-out ───────────────────────────────────────
+
+/// Aggregate snapshot/size/file counts across every repo a `MultiRunner` queried successfully,
+/// plus the union of hostnames seen. Repos that failed to query are reported separately and
+/// excluded from these totals.
+#[derive(Debug, Default, Serialize)]
+pub struct AggregateStatus {
+    pub total_snapshots: usize,
+    pub total_size: u64,
+    pub total_files: u64,
+    pub hosts: Vec<String>,
+}
+
+/// Runs the existing single-repo queries (`snapshots`, `stats`) across every configured
+/// repository and aggregates the results, so an operator watching several restic targets (e.g.
+/// local + offsite) doesn't have to invoke exarp once per repo.
+pub struct MultiRunner {
+    repos: Vec<(String, ResticRunner)>,
+}
+
+impl MultiRunner {
+    /// Build one `ResticRunner` per `[[repositories]]` entry, plus one for `[restic]` (named
+    /// "default") if it has a repository configured. Errors if neither is present.
+    pub fn from_config(config: &ExarpConfig) -> Result<Self> {
+        let mut repos = Vec::new();
+
+        if config.restic.repository.is_some() {
+            repos.push(("default".to_string(), ResticRunner::from_config(config)));
+        }
+
+        for target in &config.repositories {
+            let binary = target.binary.clone().or_else(|| config.restic.binary.clone());
+            repos.push((
+                target.name.clone(),
+                ResticRunner::new(binary, Some(target.repository.clone()), target.password_file.clone()),
+            ));
+        }
+
+        if repos.is_empty() {
+            bail!("No repositories configured. Add [restic] or at least one [[repositories]] entry");
+        }
+
+        Ok(Self { repos })
+    }
+
+    /// Query `snapshots` + `stats` for every repo concurrently, returning each repo's name
+    /// alongside its result so a failed repo doesn't sink the whole fan-out.
+    fn status_all(&self) -> Vec<(String, Result<(Vec<Snapshot>, RepoStats)>)> {
+        self.repos
+            .par_iter()
+            .map(|(name, runner)| {
+                let result = runner.snapshots().and_then(|snaps| Ok((snaps, runner.stats()?)));
+                (name.clone(), result)
+            })
+            .collect()
+    }
 }
 
 // ── Display helpers ────────────────────────────────────────────────
@@ -251,45 +432,138 @@ fn time_ago(time_str: &str) -> String {
     }
 }
 
+// ── Drift anomaly detection ────────────────────────────────────────
+
+/// Need this many pairs before a modified z-score means anything — with fewer, a single
+/// snapshot pair defines its own median and always scores 0.
+const MIN_PAIRS_FOR_SCORING: usize = 4;
+/// Standard Iglewicz–Hoaglin cutoff: a modified z-score above this flags a pair as anomalous.
+const MODIFIED_Z_THRESHOLD: f64 = 3.5;
+/// Guards the mean/stddev fallback below from dividing by zero when every value is identical.
+const STD_DEV_EPSILON: f64 = 1e-9;
+
+fn median(sorted_scratch: &mut [f64]) -> f64 {
+    sorted_scratch.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let n = sorted_scratch.len();
+    if n % 2 == 1 {
+        sorted_scratch[n / 2]
+    } else {
+        (sorted_scratch[n / 2 - 1] + sorted_scratch[n / 2]) / 2.0
+    }
+}
+
+/// Modified z-score per Iglewicz & Hoaglin: `0.6745 * (x - median) / MAD`. Robust to the
+/// outliers it's trying to detect, unlike a plain mean/stddev z-score. Falls back to
+/// mean/stddev (with an epsilon guard) when MAD is zero — e.g. a history where every pair but
+/// one has identical churn, which would otherwise divide by zero.
+fn modified_z_scores(values: &[f64]) -> Vec<f64> {
+    let m = median(&mut values.to_vec());
+    let mut abs_devs: Vec<f64> = values.iter().map(|v| (v - m).abs()).collect();
+    let mad = median(&mut abs_devs);
+
+    if mad > 0.0 {
+        values.iter().map(|v| 0.6745 * (v - m) / mad).collect()
+    } else {
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let variance =
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+        let std_dev = variance.sqrt().max(STD_DEV_EPSILON);
+        values.iter().map(|v| (v - mean) / std_dev).collect()
+    }
+}
+
 // ── Commands ───────────────────────────────────────────────────────
 
-pub fn cmd_status(runner: &ResticRunner, json_output: bool) -> Result<()> {
-    let snaps = runner.snapshots()?;
-    let stats = runner.stats()?;
+/// Status across every repo `multi` was built from: a per-repo breakdown table plus an
+/// aggregate block summing snapshots/bytes/files and merging hostnames.
+pub fn cmd_status_multi(multi: &MultiRunner, json_output: bool) -> Result<()> {
+    let results = multi.status_all();
+
+    let mut aggregate = AggregateStatus::default();
+    let mut host_set: HashSet<String> = HashSet::new();
 
     if json_output {
+        let mut repos = serde_json::Map::new();
+        for (name, result) in &results {
+            match result {
+                Ok((snaps, stats)) => {
+                    aggregate.total_snapshots += snaps.len();
+                    aggregate.total_size += stats.total_size;
+                    aggregate.total_files += stats.total_file_count;
+                    host_set.extend(snaps.iter().map(|s| s.hostname.clone()));
+
+                    repos.insert(
+                        name.clone(),
+                        serde_json::json!({
+                            "snapshots": snaps.len(),
+                            "total_size": stats.total_size,
+                            "total_files": stats.total_file_count,
+                            "latest_snapshot": snaps.last().map(|s| &s.time),
+                            "hosts": snaps.iter().map(|s| s.hostname.clone()).collect::<HashSet<_>>(),
+                        }),
+                    );
+                }
+                Err(err) => {
+                    repos.insert(name.clone(), serde_json::json!({ "error": err.to_string() }));
+                }
+            }
+        }
+        aggregate.hosts = host_set.into_iter().collect();
+        aggregate.hosts.sort();
+
         let out = serde_json::json!({
-            "snapshots": snaps.len(),
-            "total_size": stats.total_size,
-            "total_files": stats.total_file_count,
-            "latest_snapshot": snaps.last().map(|s| &s.time),
-            "hosts": snaps.iter().map(|s| s.hostname.clone()).collect::<std::collections::HashSet<_>>(),
+            "repos": repos,
+            "aggregate": aggregate,
         });
         println!("{}", serde_json::to_string_pretty(&out)?);
         return Ok(());
     }
 
-    println!("{}", "╔══════════════════════════════════════╗".bright_cyan());
-    println!("{}", "║     EXARP — RESTIC REPO STATUS       ║".bright_cyan());
-    println!("{}", "╚══════════════════════════════════════╝".bright_cyan());
+    println!("{}", "╔══════════════════════════════════════════════════════════╗".bright_cyan());
+    println!("{}", "║           EXARP — MULTI-REPOSITORY STATUS                ║".bright_cyan());
+    println!("{}", "╚══════════════════════════════════════════════════════════╝".bright_cyan());
     println!();
 
-    println!("  {} {}", "Snapshots:".bright_white(), snaps.len().to_string().bright_green());
-    println!("  {} {}", "Total size:".bright_white(), human_bytes(stats.total_size).bright_green());
-    println!("  {} {}", "Total files:".bright_white(), stats.total_file_count.to_string().bright_green());
-
-    if let Some(latest) = snaps.last() {
-        println!("  {} {} ({})", "Latest:".bright_white(), 
-            latest.short_id.bright_yellow(),
-            time_ago(&latest.time).bright_cyan());
+    for (name, result) in &results {
+        match result {
+            Ok((snaps, stats)) => {
+                aggregate.total_snapshots += snaps.len();
+                aggregate.total_size += stats.total_size;
+                aggregate.total_files += stats.total_file_count;
+                host_set.extend(snaps.iter().map(|s| s.hostname.clone()));
+
+                let latest = snaps
+                    .last()
+                    .map(|s| format!("{} ({})", s.short_id, time_ago(&s.time)))
+                    .unwrap_or_else(|| "none".to_string());
+
+                println!(
+                    "  {} {:<12} {:>5} snapshots  {:>10}  latest {}",
+                    "●".bright_green(),
+                    name.bright_white(),
+                    snaps.len(),
+                    human_bytes(stats.total_size).bright_green(),
+                    latest.bright_cyan()
+                );
+            }
+            Err(err) => {
+                println!("  {} {:<12} {}", "●".bright_red(), name.bright_white(), err.to_string().bright_red());
+            }
+        }
     }
 
-    // Unique hosts
-    let hosts: std::collections::HashSet<_> = snaps.iter().map(|s| &s.hostname).collect();
-    println!("  {} {}", "Hosts:".bright_white(), 
-        hosts.iter().map(|h| h.bright_magenta().to_string()).collect::<Vec<_>>().join(", "));
+    aggregate.hosts = host_set.into_iter().collect();
+    aggregate.hosts.sort();
 
     println!();
+    println!("  {} {} snapshots, {} across {} repo(s)",
+        "Aggregate:".bright_white(),
+        aggregate.total_snapshots.to_string().bright_green(),
+        human_bytes(aggregate.total_size).bright_green(),
+        results.len());
+    println!("  {} {}", "Hosts:".bright_white(), aggregate.hosts.join(", ").bright_magenta());
+    println!();
+
     Ok(())
 }
 
@@ -398,11 +672,52 @@ pub fn cmd_drift(runner: &ResticRunner, json_output: bool, config: &ExarpConfig)
 
     let mut alerts: Vec<serde_json::Value> = Vec::new();
 
-    // Compare consecutive snapshots (last N pairs)
+    // Diff every consecutive pair, not just the last few — the modified z-score below needs
+    // the full history to build a meaningful median/MAD baseline.
     let pairs: Vec<_> = snaps.windows(2).collect();
-    let check_pairs = if pairs.len() > 5 { &pairs[pairs.len()-5..] } else { &pairs };
+    let diffs: Vec<Option<DiffSummary>> = pairs
+        .iter()
+        .map(|pair| runner.diff(&pair[0].short_id, &pair[1].short_id).ok())
+        .collect();
+
+    // Two churn metrics scored independently: file-count churn and byte-volume churn. A pair
+    // is anomalous if either one's modified z-score clears the threshold. Only successful
+    // diffs feed the median/MAD baseline — a failed `restic diff` isn't "zero churn", and
+    // counting it as such would drag the baseline down and inflate every other pair's z-score.
+    let successful: Vec<usize> = diffs
+        .iter()
+        .enumerate()
+        .filter_map(|(i, d)| d.is_some().then_some(i))
+        .collect();
+
+    let file_churn: Vec<f64> = successful
+        .iter()
+        .map(|&i| {
+            let d = diffs[i].as_ref().unwrap();
+            (d.files_new + d.files_removed + d.files_changed) as f64
+        })
+        .collect();
+    let data_churn: Vec<f64> = successful
+        .iter()
+        .map(|&i| {
+            let d = diffs[i].as_ref().unwrap();
+            (d.data_added + d.data_removed) as f64
+        })
+        .collect();
+
+    let scored = successful.len() >= MIN_PAIRS_FOR_SCORING;
+    let mut file_z: Vec<Option<f64>> = vec![None; diffs.len()];
+    let mut data_z: Vec<Option<f64>> = vec![None; diffs.len()];
+    if scored {
+        let fz_vals = modified_z_scores(&file_churn);
+        let dz_vals = modified_z_scores(&data_churn);
+        for (pos, &i) in successful.iter().enumerate() {
+            file_z[i] = Some(fz_vals[pos]);
+            data_z[i] = Some(dz_vals[pos]);
+        }
+    }
 
-    for pair in check_pairs {
+    for (i, pair) in pairs.iter().enumerate() {
         let snap1 = &pair[0];
         let snap2 = &pair[1];
 
@@ -413,24 +728,28 @@ pub fn cmd_drift(runner: &ResticRunner, json_output: bool, config: &ExarpConfig)
                 snap2.short_id.bright_yellow());
         }
 
-        match runner.diff(&snap1.short_id, &snap2.short_id) {
-            Ok(diff) => {
-                let total_changes = diff.files_new + diff.files_removed + diff.files_changed;
-                let change_pct = if diff.files_new + diff.files_changed > 0 {
-                    // Rough percentage based on changes vs total (estimate)
-                    (total_changes as f64 / (total_changes as f64 + 100.0)) * 100.0
-                } else {
-                    0.0
-                };
+        match &diffs[i] {
+            Some(diff) => {
+                let fz = file_z[i];
+                let dz = data_z[i];
 
                 let mut pair_alerts = Vec::new();
 
-                // Check for mass changes
-                if change_pct > config.alerts.drift_file_change_pct {
-                    pair_alerts.push(format!("HIGH DRIFT: {:.0}% files changed", change_pct));
+                if fz.is_some_and(|z| z > MODIFIED_Z_THRESHOLD) {
+                    pair_alerts.push(format!(
+                        "HIGH DRIFT: file churn z-score {:.2} (>{:.1}) vs this repo's history",
+                        fz.unwrap(), MODIFIED_Z_THRESHOLD
+                    ));
+                }
+                if dz.is_some_and(|z| z > MODIFIED_Z_THRESHOLD) {
+                    pair_alerts.push(format!(
+                        "HIGH DRIFT: data churn z-score {:.2} (>{:.1}) vs this repo's history",
+                        dz.unwrap(), MODIFIED_Z_THRESHOLD
+                    ));
                 }
 
-                // Check for mass deletion
+                // Mass deletion ratio stays an independent rule — it catches a one-off wipe
+                // even in a short/noisy history the z-score can't yet baseline.
                 if diff.files_removed > 0 && diff.data_removed > diff.data_added {
                     let ratio = diff.data_removed as f64 / (diff.data_added.max(1)) as f64;
                     if ratio > 2.0 {
@@ -447,6 +766,8 @@ pub fn cmd_drift(runner: &ResticRunner, json_output: bool, config: &ExarpConfig)
                         "files_changed": diff.files_changed,
                         "data_added": diff.data_added,
                         "data_removed": diff.data_removed,
+                        "file_churn_z": fz,
+                        "data_churn_z": dz,
                         "alerts": pair_alerts,
                     }));
                 } else {
@@ -455,25 +776,30 @@ pub fn cmd_drift(runner: &ResticRunner, json_output: bool, config: &ExarpConfig)
                     } else {
                         pair_alerts.join("; ").bright_red().to_string()
                     };
+                    let z_label = match (fz, dz) {
+                        (Some(fz), Some(dz)) => format!("z(files)={:.2} z(data)={:.2}", fz, dz),
+                        _ => format!("z=n/a (<{MIN_PAIRS_FOR_SCORING} pairs)"),
+                    };
 
-                    println!("    +{} new, -{} removed, ~{} changed | added {} / removed {}  [{}]",
+                    println!("    +{} new, -{} removed, ~{} changed | added {} / removed {} | {}  [{}]",
                         diff.files_new.to_string().bright_green(),
                         diff.files_removed.to_string().bright_red(),
                         diff.files_changed.to_string().bright_yellow(),
                         human_bytes(diff.data_added).bright_green(),
                         human_bytes(diff.data_removed).bright_red(),
+                        z_label.dimmed(),
                         status);
                 }
             }
-            Err(e) => {
+            None => {
                 if json_output {
                     alerts.push(serde_json::json!({
                         "from": snap1.short_id,
                         "to": snap2.short_id,
-                        "error": e.to_string(),
+                        "error": "restic diff failed",
                     }));
                 } else {
-                    println!("    {} {}", "Error:".bright_red(), e);
+                    println!("    {} restic diff failed", "Error:".bright_red());
                 }
             }
         }
@@ -519,3 +845,466 @@ pub fn cmd_drift(runner: &ResticRunner, json_output: bool, config: &ExarpConfig)
 
     Ok(())
 }
+
+/// Preview `policy` against the repository and print a kept-vs-removed table per group,
+/// mirroring `cmd_snapshots`'s layout. Only forgets/prunes for real when `apply` is set —
+/// otherwise this is purely a dry-run report.
+pub fn cmd_forget(runner: &ResticRunner, policy: &ForgetPolicy, apply: bool, json_output: bool) -> Result<()> {
+    let groups = runner.forget_dry_run(policy)?;
+
+    if json_output {
+        let out = serde_json::json!({
+            "groups": groups.iter().map(|g| serde_json::json!({
+                "host": g.host,
+                "paths": g.paths,
+                "tags": g.tags,
+                "keep": g.keep.iter().map(|s| &s.short_id).collect::<Vec<_>>(),
+                "remove": g.remove.iter().map(|s| &s.short_id).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+            "applied": apply,
+        });
+        println!("{}", serde_json::to_string_pretty(&out)?);
+    } else {
+        println!("{}", "╔══════════════════════════════════════════════════════════╗".bright_cyan());
+        println!("{}", "║              EXARP — RESTIC FORGET PREVIEW               ║".bright_cyan());
+        println!("{}", "╚══════════════════════════════════════════════════════════╝".bright_cyan());
+        println!();
+
+        let total_remove: usize = groups.iter().map(|g| g.remove.len()).sum();
+        let total_keep: usize = groups.iter().map(|g| g.keep.len()).sum();
+
+        for group in &groups {
+            let label = group
+                .host
+                .as_deref()
+                .map(|h| h.to_string())
+                .unwrap_or_else(|| "(ungrouped)".to_string());
+            let paths = group.paths.as_ref().map(|p| p.join(", ")).unwrap_or_default();
+            println!("  {} {}  {}", "Group:".bright_white(), label.bright_magenta(), paths.dimmed());
+
+            for snap in &group.keep {
+                println!("    {} {}  {}", "keep  ".bright_green(), snap.short_id.bright_yellow(), snap.time.dimmed());
+            }
+            for snap in &group.remove {
+                println!("    {} {}  {}", "remove".bright_red(), snap.short_id.bright_yellow(), snap.time.dimmed());
+            }
+            println!();
+        }
+
+        println!(
+            "  {} {} snapshot(s) kept, {} snapshot(s) {}",
+            "Summary:".bright_white(),
+            total_keep.to_string().bright_green(),
+            total_remove.to_string().bright_red(),
+            if apply { "removed" } else { "would be removed" }.bright_red()
+        );
+    }
+
+    if apply {
+        if !json_output {
+            println!();
+            println!("  {} Applying policy (forget + prune)...", "→".bright_cyan());
+        }
+        let output = runner.forget_apply(policy)?;
+        if !json_output {
+            for line in output.lines() {
+                println!("  {} {}", "│".dimmed(), line.dimmed());
+            }
+            println!("  {} Policy applied", "✓".bright_green());
+        }
+    } else if !json_output {
+        println!();
+        println!("  {} Dry run only — pass --apply to forget and prune for real", "ℹ".bright_cyan());
+    }
+
+    Ok(())
+}
+
+// ── Watch daemon ───────────────────────────────────────────────────
+
+/// One of the three one-shot commands above, run on a standing interval instead of once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WatchTaskKind {
+    Status,
+    Check,
+    Drift,
+}
+
+impl WatchTaskKind {
+    fn label(&self) -> &'static str {
+        match self {
+            WatchTaskKind::Status => "status",
+            WatchTaskKind::Check => "check",
+            WatchTaskKind::Drift => "drift",
+        }
+    }
+}
+
+struct ScheduledTask {
+    kind: WatchTaskKind,
+    interval: std::time::Duration,
+    next_run: std::time::Instant,
+}
+
+/// Tracks each task's last observed result so a cycle only prints when something actually
+/// changed (healthy→stale, new anomaly, check failure) rather than on every poll.
+#[derive(Default)]
+struct WatchState {
+    stale: Option<bool>,
+    healthy: Option<bool>,
+    drifting: Option<bool>,
+}
+
+/// Resident monitor: keeps a small in-memory schedule of status/check/drift tasks, each on its
+/// own interval from `[watch]` in config.toml, sleeps until the nearest `next_run`, runs the due
+/// task, and reschedules it — a simple priority-by-timestamp loop. Runs until killed; only
+/// prints a line (or JSON event, with `json_output`) when a task's state changes.
+pub fn cmd_watch(runner: &ResticRunner, config: &ExarpConfig, json_output: bool) -> Result<()> {
+    let wc = &config.watch;
+    let now = std::time::Instant::now();
+
+    let mut schedule: Vec<ScheduledTask> = Vec::new();
+    if wc.enable_status {
+        schedule.push(ScheduledTask {
+            kind: WatchTaskKind::Status,
+            interval: std::time::Duration::from_secs(wc.status_interval_secs.max(1)),
+            next_run: now,
+        });
+    }
+    if wc.enable_check {
+        schedule.push(ScheduledTask {
+            kind: WatchTaskKind::Check,
+            interval: std::time::Duration::from_secs(wc.check_interval_secs.max(1)),
+            next_run: now,
+        });
+    }
+    if wc.enable_drift {
+        schedule.push(ScheduledTask {
+            kind: WatchTaskKind::Drift,
+            interval: std::time::Duration::from_secs(wc.drift_interval_secs.max(1)),
+            next_run: now,
+        });
+    }
+
+    if schedule.is_empty() {
+        bail!("No watch tasks enabled — set at least one of [watch] enable_status/enable_check/enable_drift");
+    }
+
+    if !json_output {
+        println!("{}", "🛡️  Exarp Restic Watch".cyan().bold());
+        for task in &schedule {
+            println!("  {} every {}s", task.kind.label(), task.interval.as_secs());
+        }
+        println!("  Press Ctrl+C to stop\n");
+    }
+
+    let mut state = WatchState::default();
+
+    loop {
+        schedule.sort_by_key(|t| t.next_run);
+        let wait = schedule[0].next_run.saturating_duration_since(std::time::Instant::now());
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+
+        let kind = schedule[0].kind;
+        schedule[0].next_run = std::time::Instant::now() + schedule[0].interval;
+
+        match kind {
+            WatchTaskKind::Status => run_watch_status(runner, config, &mut state, json_output)?,
+            WatchTaskKind::Check => run_watch_check(runner, &mut state, json_output)?,
+            WatchTaskKind::Drift => run_watch_drift(runner, &mut state, json_output)?,
+        }
+    }
+}
+
+fn watch_emit(json_output: bool, event: &str, human: String, json_extra: serde_json::Value) {
+    if json_output {
+        let mut out = serde_json::json!({ "event": event, "time": Utc::now().to_rfc3339() });
+        if let (Some(obj), Some(extra)) = (out.as_object_mut(), json_extra.as_object()) {
+            obj.extend(extra.clone());
+        }
+        println!("{}", out);
+    } else {
+        println!("  {human}");
+    }
+}
+
+fn run_watch_status(
+    runner: &ResticRunner,
+    config: &ExarpConfig,
+    state: &mut WatchState,
+    json_output: bool,
+) -> Result<()> {
+    let snaps = runner.snapshots()?;
+    let Some(latest) = snaps.last() else { return Ok(()) };
+    let Ok(dt) = DateTime::parse_from_rfc3339(&latest.time) else { return Ok(()) };
+
+    let hours_since = Utc::now().signed_duration_since(dt.with_timezone(&Utc)).num_hours();
+    let threshold = config.alerts.missed_backup_hours as i64;
+    let stale = hours_since > threshold;
+
+    if state.stale != Some(stale) {
+        let was_stale = state.stale == Some(true);
+        state.stale = Some(stale);
+        if stale {
+            watch_emit(
+                json_output,
+                "missed_backup",
+                format!("{} Last backup {}h ago (threshold {}h)", "⚠ STALE".bright_red().bold(), hours_since, threshold),
+                serde_json::json!({ "hours_since_last": hours_since, "threshold": threshold }),
+            );
+        } else {
+            watch_emit(
+                json_output,
+                "backup_fresh",
+                format!(
+                    "{} Backups fresh{} ({}h ago)",
+                    "✓".bright_green(),
+                    if was_stale { " again" } else { "" },
+                    hours_since
+                ),
+                serde_json::json!({ "hours_since_last": hours_since, "threshold": threshold }),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn run_watch_check(runner: &ResticRunner, state: &mut WatchState, json_output: bool) -> Result<()> {
+    let (healthy, output) = runner.check()?;
+
+    if state.healthy != Some(healthy) {
+        state.healthy = Some(healthy);
+        if healthy {
+            watch_emit(json_output, "check_ok", format!("{} Repository check passed", "✓".bright_green()), serde_json::json!({}));
+        } else {
+            watch_emit(
+                json_output,
+                "check_failed",
+                format!("{} Repository check FAILED", "✗".bright_red().bold()),
+                serde_json::json!({ "output": output.trim() }),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn run_watch_drift(runner: &ResticRunner, state: &mut WatchState, json_output: bool) -> Result<()> {
+    let snaps = runner.snapshots()?;
+    if snaps.len() < 2 {
+        return Ok(());
+    }
+
+    let snap1 = &snaps[snaps.len() - 2];
+    let snap2 = &snaps[snaps.len() - 1];
+    let diff = runner.diff(&snap1.short_id, &snap2.short_id)?;
+
+    let drifting = diff.files_removed > 0
+        && diff.data_removed > diff.data_added
+        && diff.data_removed as f64 / (diff.data_added.max(1)) as f64 > 2.0;
+
+    if state.drifting != Some(drifting) && drifting {
+        state.drifting = Some(drifting);
+        watch_emit(
+            json_output,
+            "drift_anomaly",
+            format!(
+                "{} Mass deletion between {} → {}: -{} / +{} bytes",
+                "⚠ DRIFT".bright_red().bold(),
+                snap1.short_id,
+                snap2.short_id,
+                human_bytes(diff.data_removed),
+                human_bytes(diff.data_added)
+            ),
+            serde_json::json!({ "from": snap1.short_id, "to": snap2.short_id, "data_removed": diff.data_removed, "data_added": diff.data_added }),
+        );
+    } else if state.drifting != Some(drifting) {
+        state.drifting = Some(drifting);
+    }
+
+    Ok(())
+}
+
+// ── Prometheus metrics exporter ────────────────────────────────────
+
+/// Diff at most this many of the most recent snapshot pairs for the drift gauges — a full-history
+/// diff on every scrape would make `/metrics` scrape latency grow with repo age.
+const METRICS_MAX_DRIFT_PAIRS: usize = 5;
+
+/// Caches the results of the restic shell-outs `metrics_text` depends on, each refreshed no more
+/// often than the matching `[watch]` interval — the same cadence the resident watch daemon
+/// already polls at. Without this, a Prometheus scraper hitting `/metrics` every 15-60s would
+/// re-run `restic check` (a full-repo integrity pass that can take minutes), `stats`, and up to
+/// `METRICS_MAX_DRIFT_PAIRS` `diff`s on every single request.
+#[derive(Default)]
+pub struct MetricsCache {
+    status: Option<(Vec<Snapshot>, RepoStats, Instant)>,
+    check: Option<(bool, Instant)>,
+    diffs: HashMap<(String, String), (DiffSummary, Instant)>,
+}
+
+impl MetricsCache {
+    fn status(&mut self, runner: &ResticRunner, ttl: std::time::Duration) -> Result<&(Vec<Snapshot>, RepoStats, Instant)> {
+        let fresh = self.status.as_ref().is_some_and(|(.., at)| at.elapsed() < ttl);
+        if !fresh {
+            let snaps = runner.snapshots()?;
+            let stats = runner.stats()?;
+            self.status = Some((snaps, stats, Instant::now()));
+        }
+        Ok(self.status.as_ref().unwrap())
+    }
+
+    fn check(&mut self, runner: &ResticRunner, ttl: std::time::Duration) -> Result<bool> {
+        let fresh = self.check.as_ref().is_some_and(|(_, at)| at.elapsed() < ttl);
+        if !fresh {
+            let (healthy, _) = runner.check()?;
+            self.check = Some((healthy, Instant::now()));
+        }
+        Ok(self.check.as_ref().unwrap().0)
+    }
+
+    fn diff(&mut self, runner: &ResticRunner, from: &str, to: &str, ttl: std::time::Duration) -> Result<DiffSummary> {
+        let key = (from.to_string(), to.to_string());
+        let fresh = self.diffs.get(&key).is_some_and(|(_, at)| at.elapsed() < ttl);
+        if !fresh {
+            let diff = runner.diff(from, to)?;
+            self.diffs.insert(key.clone(), (diff, Instant::now()));
+        }
+        Ok(self.diffs[&key].0.clone())
+    }
+}
+
+/// Render `cmd_status_multi`/`cmd_drift`'s numbers as Prometheus exposition format text, suitable
+/// for `--metrics` stdout (node_exporter textfile collector) or serving over HTTP. Expensive
+/// restic calls are served out of `cache`, refreshed at most once per their `[watch]` interval.
+fn metrics_text(runner: &ResticRunner, cache: &mut MetricsCache, config: &ExarpConfig) -> Result<String> {
+    let wc = &config.watch;
+    let status_ttl = std::time::Duration::from_secs(wc.status_interval_secs.max(1));
+    let check_ttl = std::time::Duration::from_secs(wc.check_interval_secs.max(1));
+    let drift_ttl = std::time::Duration::from_secs(wc.drift_interval_secs.max(1));
+
+    // Clone the cached snapshot list and copy the cheap scalar stats out so the `status` borrow
+    // doesn't overlap with the `check`/`diff` cache calls below.
+    let (cached_snaps, cached_stats, _) = cache.status(runner, status_ttl)?;
+    let snaps = cached_snaps.clone();
+    let total_size = cached_stats.total_size;
+    let total_file_count = cached_stats.total_file_count;
+
+    let healthy = cache.check(runner, check_ttl)?;
+
+    let mut out = String::new();
+
+    out.push_str("# HELP exarp_snapshots_total Number of snapshots in the repository\n");
+    out.push_str("# TYPE exarp_snapshots_total gauge\n");
+    out.push_str(&format!("exarp_snapshots_total {}\n", snaps.len()));
+
+    out.push_str("# HELP exarp_repo_size_bytes Total size of the repository in bytes\n");
+    out.push_str("# TYPE exarp_repo_size_bytes gauge\n");
+    out.push_str(&format!("exarp_repo_size_bytes {}\n", total_size));
+
+    out.push_str("# HELP exarp_repo_files_total Total number of files tracked in the repository\n");
+    out.push_str("# TYPE exarp_repo_files_total gauge\n");
+    out.push_str(&format!("exarp_repo_files_total {}\n", total_file_count));
+
+    out.push_str("# HELP exarp_check_healthy Whether the last restic check succeeded (1) or failed (0)\n");
+    out.push_str("# TYPE exarp_check_healthy gauge\n");
+    out.push_str(&format!("exarp_check_healthy {}\n", if healthy { 1 } else { 0 }));
+
+    if let Some(latest) = snaps.last() {
+        if let Ok(dt) = DateTime::parse_from_rfc3339(&latest.time) {
+            let age_secs = Utc::now().signed_duration_since(dt.with_timezone(&Utc)).num_seconds().max(0);
+            out.push_str("# HELP exarp_last_snapshot_age_seconds Seconds since the most recent snapshot\n");
+            out.push_str("# TYPE exarp_last_snapshot_age_seconds gauge\n");
+            out.push_str(&format!("exarp_last_snapshot_age_seconds {}\n", age_secs));
+        }
+    }
+
+    if snaps.len() >= 2 {
+        let pairs: Vec<_> = snaps.windows(2).rev().take(METRICS_MAX_DRIFT_PAIRS).collect();
+
+        out.push_str("# HELP exarp_drift_files_changed Files added, removed, or changed between a snapshot pair\n");
+        out.push_str("# TYPE exarp_drift_files_changed gauge\n");
+        out.push_str("# HELP exarp_drift_data_bytes Bytes added or removed between a snapshot pair\n");
+        out.push_str("# TYPE exarp_drift_data_bytes gauge\n");
+
+        for pair in pairs.iter().rev() {
+            let (snap1, snap2) = (&pair[0], &pair[1]);
+            let Ok(diff) = cache.diff(runner, &snap1.short_id, &snap2.short_id, drift_ttl) else { continue };
+            let churn = diff.files_new + diff.files_removed + diff.files_changed;
+            let labels = format!(
+                r#"from="{}",to="{}",host="{}""#,
+                snap1.short_id, snap2.short_id, snap2.hostname
+            );
+            out.push_str(&format!("exarp_drift_files_changed{{{labels}}} {}\n", churn));
+            out.push_str(&format!(
+                "exarp_drift_data_bytes{{{labels},direction=\"added\"}} {}\n",
+                diff.data_added
+            ));
+            out.push_str(&format!(
+                "exarp_drift_data_bytes{{{labels},direction=\"removed\"}} {}\n",
+                diff.data_removed
+            ));
+        }
+    }
+
+    Ok(out)
+}
+
+/// `exarp restic status --metrics`: print the current repo's numbers in Prometheus exposition format to
+/// stdout, for a node_exporter textfile-collector cron job. A one-shot process gets a fresh
+/// `MetricsCache` every invocation, so this always computes live numbers — cron already controls
+/// how often that happens.
+pub fn cmd_metrics(runner: &ResticRunner, config: &ExarpConfig) -> Result<()> {
+    let mut cache = MetricsCache::default();
+    print!("{}", metrics_text(runner, &mut cache, config)?);
+    Ok(())
+}
+
+/// Minimal blocking HTTP server answering `GET /metrics` so a Prometheus scraper can pull live
+/// numbers instead of relying on a textfile-collector cron job. One request at a time — this is
+/// a low-traffic sidecar endpoint, not a production web server. A single `MetricsCache` lives for
+/// the whole server run, so repeated scrapes reuse the last `check`/`stats`/`diff` results instead
+/// of re-running them — otherwise a scraper polling every 15-60s would re-run a full `restic
+/// check` (which can take minutes) on every request.
+pub fn serve_metrics(runner: &ResticRunner, config: &ExarpConfig, addr: &str) -> Result<()> {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind(addr).context(format!("Failed to bind metrics endpoint on {addr}"))?;
+    println!("  {} Serving Prometheus metrics on http://{addr}/metrics", "→".bright_cyan());
+
+    let mut cache = MetricsCache::default();
+
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(s) => s,
+            Err(_) => continue,
+        };
+
+        let mut buf = [0u8; 1024];
+        let _ = stream.read(&mut buf);
+
+        let response = match metrics_text(runner, &mut cache, config) {
+            Ok(body) => format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            ),
+            Err(err) => {
+                let body = format!("error collecting metrics: {err}");
+                format!(
+                    "HTTP/1.1 500 Internal Server Error\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+            }
+        };
+
+        let _ = stream.write_all(response.as_bytes());
+    }
+
+    Ok(())
+}
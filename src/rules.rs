@@ -0,0 +1,172 @@
+use crate::config::AlertConfig;
+
+/// How loud a `Diagnostic` is in the Alerts pane — also its sort key (worst first).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warn,
+    Critical,
+}
+
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Everything a `Rule` needs to judge one source: its entropy history, file/byte counts for
+/// this scan and the one before it, and how long it's been since a successful scan.
+pub struct RuleContext<'a> {
+    pub name: &'a str,
+    pub entropy_history: &'a [f64],
+    pub files: usize,
+    pub prev_files: Option<usize>,
+    pub total_bytes: u64,
+    pub prev_total_bytes: Option<u64>,
+    pub hours_since_scan: f64,
+}
+
+/// One detection heuristic, decoupled from how its results get rendered — mirrors how a
+/// linter separates rule definitions from the runner that maps them to severities.
+pub trait Rule {
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Diagnostic>;
+}
+
+/// Flags a source whose entropy has climbed sharply across its tracked history — the
+/// population-wide drift toward ~8.0 bits/byte that indicates encryption in progress.
+pub struct EntropySpikeRule {
+    pub threshold: f64,
+}
+
+impl Rule for EntropySpikeRule {
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let (Some(&first), Some(&last)) = (ctx.entropy_history.first(), ctx.entropy_history.last())
+        else {
+            return Vec::new();
+        };
+        let delta = last - first;
+        if delta >= self.threshold {
+            vec![Diagnostic {
+                severity: Severity::Critical,
+                message: format!(
+                    "{}: entropy climbed {:+.2} bits/byte ({:.2} \u{2192} {:.2})",
+                    ctx.name, delta, first, last
+                ),
+            }]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Flags a source whose file count changed by more than `AlertConfig::drift_file_change_pct`
+/// between scans — a burst of creates/deletes/renames consistent with mass mutation.
+pub struct MassFileChangeRule {
+    pub pct: f64,
+}
+
+impl Rule for MassFileChangeRule {
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let Some(prev) = ctx.prev_files else {
+            return Vec::new();
+        };
+        if prev == 0 {
+            return Vec::new();
+        }
+        let change_pct = (ctx.files as f64 - prev as f64).abs() / prev as f64 * 100.0;
+        if change_pct >= self.pct {
+            vec![Diagnostic {
+                severity: Severity::Warn,
+                message: format!(
+                    "{}: file count changed {:.1}% ({} \u{2192} {})",
+                    ctx.name, change_pct, prev, ctx.files
+                ),
+            }]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Flags a source whose total tracked size dropped by more than
+/// `AlertConfig::drift_size_decrease_pct` — the signature of a deletion attack.
+pub struct SizeCollapseRule {
+    pub pct: f64,
+}
+
+impl Rule for SizeCollapseRule {
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        let Some(prev) = ctx.prev_total_bytes else {
+            return Vec::new();
+        };
+        if prev == 0 {
+            return Vec::new();
+        }
+        let decrease_pct = (prev as f64 - ctx.total_bytes as f64) / prev as f64 * 100.0;
+        if decrease_pct >= self.pct {
+            vec![Diagnostic {
+                severity: Severity::Critical,
+                message: format!(
+                    "{}: total size dropped {:.1}% — possible deletion attack",
+                    ctx.name, decrease_pct
+                ),
+            }]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Flags a source that hasn't scanned cleanly within `AlertConfig::missed_backup_hours`.
+pub struct MissedBackupRule {
+    pub hours: u64,
+}
+
+impl Rule for MissedBackupRule {
+    fn evaluate(&self, ctx: &RuleContext) -> Vec<Diagnostic> {
+        if ctx.hours_since_scan >= self.hours as f64 {
+            vec![Diagnostic {
+                severity: Severity::Warn,
+                message: format!(
+                    "{}: {:.0}h since last successful scan (expected within {}h)",
+                    ctx.name, ctx.hours_since_scan, self.hours
+                ),
+            }]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Build the enabled rule set, tuned and toggled from the user's config instead of hardcoded
+/// constants — each rule is included only if its `enable_*` flag is set.
+pub fn default_rules(config: &AlertConfig) -> Vec<Box<dyn Rule>> {
+    let mut rules: Vec<Box<dyn Rule>> = Vec::new();
+    if config.enable_entropy_spike {
+        rules.push(Box::new(EntropySpikeRule {
+            threshold: config.entropy_spike_threshold,
+        }));
+    }
+    if config.enable_mass_file_change {
+        rules.push(Box::new(MassFileChangeRule {
+            pct: config.drift_file_change_pct,
+        }));
+    }
+    if config.enable_size_collapse {
+        rules.push(Box::new(SizeCollapseRule {
+            pct: config.drift_size_decrease_pct,
+        }));
+    }
+    if config.enable_missed_backup {
+        rules.push(Box::new(MissedBackupRule {
+            hours: config.missed_backup_hours,
+        }));
+    }
+    rules
+}
+
+/// Run every rule against a context and return diagnostics sorted worst-first.
+pub fn run_rules(rules: &[Box<dyn Rule>], ctx: &RuleContext) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = rules.iter().flat_map(|r| r.evaluate(ctx)).collect();
+    diagnostics.sort_by(|a, b| b.severity.cmp(&a.severity));
+    diagnostics
+}
@@ -0,0 +1,117 @@
+use crate::{Alert, CheckResult};
+use anyhow::{Context, Result};
+use colored::*;
+use serde_json::json;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Bounded retries with exponential backoff so a flaky endpoint can't stall the `Watch` loop
+const MAX_ATTEMPTS: u32 = 3;
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Payload shape for a webhook destination. Auto-detected from the URL, or overridden with
+/// `--webhook-format` when a sink lives behind a proxy/relay that hides the real host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookFormat {
+    Generic,
+    Slack,
+    Discord,
+}
+
+impl WebhookFormat {
+    pub fn detect(url: &str) -> Self {
+        if url.contains("hooks.slack.com") {
+            WebhookFormat::Slack
+        } else if url.contains("discord.com/api/webhooks") || url.contains("discordapp.com/api/webhooks")
+        {
+            WebhookFormat::Discord
+        } else {
+            WebhookFormat::Generic
+        }
+    }
+}
+
+impl FromStr for WebhookFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "generic" => Ok(WebhookFormat::Generic),
+            "slack" => Ok(WebhookFormat::Slack),
+            "discord" => Ok(WebhookFormat::Discord),
+            other => anyhow::bail!("unknown webhook format '{other}' (expected generic, slack, or discord)"),
+        }
+    }
+}
+
+/// Only HIGH/CRITICAL results are worth paging someone about
+fn should_notify(result: &CheckResult) -> bool {
+    matches!(result.severity.as_str(), "HIGH" | "CRITICAL")
+}
+
+fn format_alerts(alerts: &[Alert]) -> String {
+    alerts
+        .iter()
+        .map(|a| format!("• [{}] {}: {}", a.severity, a.signal, a.message))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn payload_for(format: WebhookFormat, result: &CheckResult) -> serde_json::Value {
+    let summary = format!(
+        "[{}] entropy delta {:+.2} bits/byte vs baseline from {} — {} alert(s)",
+        result.severity,
+        result.entropy_delta,
+        result.baseline_time,
+        result.alerts.len()
+    );
+
+    match format {
+        WebhookFormat::Slack => json!({ "text": format!("{summary}\n{}", format_alerts(&result.alerts)) }),
+        WebhookFormat::Discord => json!({ "content": format!("{summary}\n{}", format_alerts(&result.alerts)) }),
+        WebhookFormat::Generic => json!({
+            "severity": result.severity,
+            "timestamp": result.timestamp,
+            "baseline_time": result.baseline_time,
+            "entropy_delta": result.entropy_delta,
+            "alerts": result.alerts,
+        }),
+    }
+}
+
+/// POST a `CheckResult` to a webhook, gated on severity, with bounded retries/backoff and a
+/// timeout so a flaky sink can't stall the caller (the `Watch` loop in particular).
+pub fn notify(url: &str, format: Option<WebhookFormat>, result: &CheckResult) -> Result<()> {
+    if !should_notify(result) {
+        return Ok(());
+    }
+
+    let format = format.unwrap_or_else(|| WebhookFormat::detect(url));
+    let body = payload_for(format, result);
+    let agent = ureq::AgentBuilder::new().timeout(REQUEST_TIMEOUT).build();
+
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        match agent.post(url).send_json(body.clone()) {
+            Ok(_) => return Ok(()),
+            Err(err) if attempt < MAX_ATTEMPTS => {
+                let backoff = BASE_BACKOFF * 2u32.pow(attempt - 1);
+                eprintln!(
+                    "  {} webhook delivery failed (attempt {}/{}): {} — retrying in {:?}",
+                    "⚠".yellow(),
+                    attempt,
+                    MAX_ATTEMPTS,
+                    err,
+                    backoff
+                );
+                std::thread::sleep(backoff);
+            }
+            Err(err) => {
+                return Err(err)
+                    .context(format!("webhook delivery failed after {MAX_ATTEMPTS} attempts"));
+            }
+        }
+    }
+}